@@ -1,4 +1,39 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// Environment variable overriding the block size CloudSeedCore processes internally.
+const MAX_BUFFER_SIZE_ENV: &str = "CLOUDSEED_MAX_BUFFER_SIZE";
+const DEFAULT_MAX_BUFFER_SIZE: u32 = 1024;
+/// CloudSeedCore keeps about a dozen buffers of this size on the stack while processing,
+/// so larger values risk overflowing the audio thread's stack.
+const MAX_BUFFER_SIZE_RANGE: std::ops::RangeInclusive<u32> = 16..=8192;
+
+fn max_buffer_size() -> u32 {
+    let Ok(value) = env::var(MAX_BUFFER_SIZE_ENV) else {
+        return DEFAULT_MAX_BUFFER_SIZE;
+    };
+    match value.trim().parse() {
+        Ok(size) if MAX_BUFFER_SIZE_RANGE.contains(&size) => size,
+        _ => panic!(
+            "{MAX_BUFFER_SIZE_ENV} must be a whole number in range {}..={}, got {value:?}",
+            MAX_BUFFER_SIZE_RANGE.start(),
+            MAX_BUFFER_SIZE_RANGE.end()
+        ),
+    }
+}
+
 fn main() {
+    let max_buffer_size = max_buffer_size();
+
+    // expose the block size to the Rust side, see MAX_INTERNAL_BLOCK_SIZE
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    fs::write(
+        out_dir.join("config.rs"),
+        format!("pub const MAX_INTERNAL_BLOCK_SIZE: u32 = {max_buffer_size};\n"),
+    )
+    .unwrap();
+
     // build the cxx bridge
     let mut bridge = cxx_build::bridge("src/bridge.rs");
 
@@ -9,7 +44,10 @@ fn main() {
         .include("src/cxx")
         .include("vendor/CloudSeedCore/include")
         // Preprocessor definitions expected by CloudSeedCore
-        .define("BUFFER_SIZE", Some("1024"))
+        .define(
+            "MAX_BUFFER_SIZE",
+            Some(max_buffer_size.to_string().as_str()),
+        )
         .define("MAX_STR_SIZE", Some("32"))
         // CloudSeedCore cpp files
         .file("vendor/CloudSeedCore/Parameters.cpp")
@@ -22,5 +60,6 @@ fn main() {
     println!("cargo:rerun-if-changed=src/bridge.rs");
     println!("cargo:rerun-if-changed=src/cxx/cloudseed_bridge.h");
     println!("cargo:rerun-if-changed=src/cxx/cloudseed_bridge.cpp");
-    println!("cargo:rerun-if-changed=CloudSeedCore");
+    println!("cargo:rerun-if-changed=vendor/CloudSeedCore");
+    println!("cargo:rerun-if-env-changed={MAX_BUFFER_SIZE_ENV}");
 }
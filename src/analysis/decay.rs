@@ -0,0 +1,142 @@
+use crate::StereoBuffer;
+
+/// Decay times, clarity and definition of an impulse response as defined by ISO 3382-1.
+///
+/// Times are in seconds and measured from the onset of the response,
+/// the first sample within 20 dB of the peak.
+/// Decay times are `None` if the response doesn't decay far enough to measure them.
+/// For a silent response, all values are `None` or NaN.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AcousticMetrics {
+    /// Reverberation time: T30 if the response decays far enough, T20 otherwise.
+    pub rt60: Option<f32>,
+    /// Reverberation time extrapolated from the decay from -5 to -25 dB.
+    pub t20: Option<f32>,
+    /// Reverberation time extrapolated from the decay from -5 to -35 dB.
+    pub t30: Option<f32>,
+    /// Early decay time, extrapolated from the decay from 0 to -10 dB.
+    pub edt: Option<f32>,
+    /// Clarity: ratio of the energy in the first 50 ms to the energy after it, in dB.
+    pub c50: f32,
+    /// Clarity: ratio of the energy in the first 80 ms to the energy after it, in dB.
+    pub c80: f32,
+    /// Definition: fraction of the energy in the first 50 ms, in range 0..=1.
+    pub d50: f32,
+    /// Centre time: centre of gravity of the energy.
+    pub centre_time: f32,
+}
+
+impl AcousticMetrics {
+    /// Measures an impulse response, summing the energy of both channels.
+    pub fn from_impulse_response(ir: &StereoBuffer) -> Self {
+        let energy = energy(ir);
+        let energy = &energy[onset(&energy)..];
+
+        let curve = backward_integrate(energy);
+        let seconds = |samples: usize| samples as f64 / ir.sample_rate as f64;
+        let decay_time = |upper_db: f64, lower_db: f64| {
+            decay_slope(&curve, upper_db, lower_db).map(|per_sample| {
+                let db_per_second = per_sample * ir.sample_rate as f64;
+                (-60.0 / db_per_second) as f32
+            })
+        };
+        let t20 = decay_time(-5.0, -25.0);
+        let t30 = decay_time(-5.0, -35.0);
+        let edt = decay_time(0.0, -10.0);
+
+        let total: f64 = energy.iter().sum();
+        let early = |ms: f64| -> f64 {
+            let len = (ms / 1000.0 * ir.sample_rate as f64).round() as usize;
+            energy[..len.min(energy.len())].iter().sum()
+        };
+        let clarity = |ms: f64| {
+            let early = early(ms);
+            (10.0 * (early / (total - early)).log10()) as f32
+        };
+        let centre_time = energy
+            .iter()
+            .enumerate()
+            .map(|(i, &e)| seconds(i) * e)
+            .sum::<f64>()
+            / total;
+
+        Self {
+            rt60: t30.or(t20),
+            t20,
+            t30,
+            edt,
+            c50: clarity(50.0),
+            c80: clarity(80.0),
+            d50: (early(50.0) / total) as f32,
+            centre_time: centre_time as f32,
+        }
+    }
+}
+
+/// Returns the Schroeder decay curve of an impulse response in dB:
+/// the energy remaining from each sample to the end relative to the total energy,
+/// summed over both channels.
+/// The curve starts at 0 dB and falls to -inf after the last non-zero sample.
+pub fn schroeder_curve(ir: &StereoBuffer) -> Vec<f32> {
+    backward_integrate(&energy(ir))
+        .iter()
+        .map(|&db| db as f32)
+        .collect()
+}
+
+/// Returns the energy of each frame, summed over both channels.
+pub(super) fn energy(ir: &StereoBuffer) -> Vec<f64> {
+    ir.left
+        .iter()
+        .zip(&ir.right)
+        .map(|(&l, &r)| (l as f64).powi(2) + (r as f64).powi(2))
+        .collect()
+}
+
+/// Returns the index of the onset of a response given the energy of each frame,
+/// the first frame within 20 dB of the peak.
+pub(super) fn onset(energy: &[f64]) -> usize {
+    let peak = energy.iter().copied().fold(0.0, f64::max);
+    energy.iter().position(|&e| e >= peak * 0.01).unwrap_or(0)
+}
+
+/// Integrates the energy backwards from the end, in dB relative to the total.
+fn backward_integrate(energy: &[f64]) -> Vec<f64> {
+    let mut remaining = 0.0;
+    let mut curve: Vec<f64> = energy
+        .iter()
+        .rev()
+        .map(|&e| {
+            remaining += e;
+            remaining
+        })
+        .collect();
+    curve.reverse();
+    let total = curve.first().copied().unwrap_or(0.0);
+    for e in &mut curve {
+        *e = 10.0 * (*e / total).log10();
+    }
+    curve
+}
+
+/// Fits a line to the decay curve between the first samples below `upper_db`
+/// and `lower_db` with least squares, returning its slope in dB per point of the curve.
+pub(super) fn decay_slope(curve: &[f64], upper_db: f64, lower_db: f64) -> Option<f64> {
+    let start = curve.iter().position(|&db| db <= upper_db)?;
+    let end = start + curve[start..].iter().position(|&db| db < lower_db)?;
+    if end - start < 2 {
+        return None;
+    }
+
+    let n = (end - start) as f64;
+    let mean_x = (start + end - 1) as f64 / 2.0;
+    let mean_y = curve[start..end].iter().sum::<f64>() / n;
+    let (mut covariance, mut variance) = (0.0, 0.0);
+    for (i, &y) in curve[start..end].iter().enumerate() {
+        let dx = (start + i) as f64 - mean_x;
+        covariance += dx * (y - mean_y);
+        variance += dx * dx;
+    }
+    let slope = covariance / variance;
+    (slope < 0.0).then_some(slope)
+}
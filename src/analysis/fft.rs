@@ -0,0 +1,108 @@
+use std::f64::consts::PI;
+use std::ops::{Add, Mul, Sub};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct Complex {
+    pub(crate) re: f64,
+    pub(crate) im: f64,
+}
+
+impl Complex {
+    pub(crate) fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+
+    pub(crate) fn norm_sqr(self) -> f64 {
+        self.re * self.re + self.im * self.im
+    }
+}
+
+impl Add for Complex {
+    type Output = Self;
+    fn add(self, o: Self) -> Self {
+        Self::new(self.re + o.re, self.im + o.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Self;
+    fn sub(self, o: Self) -> Self {
+        Self::new(self.re - o.re, self.im - o.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Self;
+    fn mul(self, o: Self) -> Self {
+        Self::new(
+            self.re * o.re - self.im * o.im,
+            self.re * o.im + self.im * o.re,
+        )
+    }
+}
+
+/// Transforms `buf` in place with an iterative radix-2 FFT, without scaling.
+/// The length must be a power of two.
+pub(crate) fn fft(buf: &mut [Complex]) {
+    let n = buf.len();
+    assert!(n.is_power_of_two(), "FFT size {n} is not a power of two");
+
+    // bit-reversal permutation
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = i
+            .reverse_bits()
+            .checked_shr(usize::BITS - bits)
+            .unwrap_or(0);
+        if i < j {
+            buf.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * PI / len as f64;
+        let step = Complex::new(angle.cos(), angle.sin());
+        for chunk in buf.chunks_exact_mut(len) {
+            let (lo, hi) = chunk.split_at_mut(len / 2);
+            let mut twiddle = Complex::new(1.0, 0.0);
+            for (a, b) in lo.iter_mut().zip(hi) {
+                let t = *b * twiddle;
+                *b = *a - t;
+                *a = *a + t;
+                twiddle = twiddle * step;
+            }
+        }
+        len *= 2;
+    }
+}
+
+/// Returns the linear convolution of `a` and `b`, computed with FFTs.
+pub(crate) fn convolve(a: &[f64], b: &[f64]) -> Vec<f64> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let len = a.len() + b.len() - 1;
+    let n = len.next_power_of_two();
+    let spectrum = |signal: &[f64]| {
+        let mut buf = vec![Complex::default(); n];
+        for (c, &x) in buf.iter_mut().zip(signal) {
+            c.re = x;
+        }
+        fft(&mut buf);
+        buf
+    };
+    let (x, y) = (spectrum(a), spectrum(b));
+
+    // the inverse transform is the forward transform of the conjugate, conjugated
+    let mut product: Vec<Complex> = x
+        .iter()
+        .zip(&y)
+        .map(|(&x, &y)| {
+            let p = x * y;
+            Complex::new(p.re, -p.im)
+        })
+        .collect();
+    fft(&mut product);
+    product[..len].iter().map(|c| c.re / n as f64).collect()
+}
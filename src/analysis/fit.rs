@@ -0,0 +1,382 @@
+use super::decay::{energy, onset, schroeder_curve};
+use super::{AcousticAnalysis, AcousticMetrics, OCTAVE_BANDS};
+use crate::reverb::assert_sample_rate;
+use crate::{DARK_PLATE, ParamId, Program, StereoBuffer, render_impulse_response};
+use std::f64::consts::PI;
+
+/// Number of points at which the decay curves of the octave bands are compared.
+const DECAY_POINTS: usize = 16;
+
+/// Level the decay curves are clamped to in dB,
+/// so the noise floor of a measured response doesn't dominate the fit.
+const DECAY_FLOOR_DB: f32 = -60.0;
+
+/// Length of the window the echo density is measured over, in seconds.
+const ECHO_DENSITY_WINDOW: f32 = 0.02;
+
+/// Number of points at which the echo density is compared,
+/// spread over the first [ECHO_DENSITY_TIME] seconds of the response.
+const ECHO_DENSITY_POINTS: usize = 10;
+const ECHO_DENSITY_TIME: f32 = 0.5;
+
+/// Fraction of samples of Gaussian noise outside one standard deviation, `erfc(1 / sqrt(2))`.
+const GAUSSIAN_OUTLIERS: f64 = 0.317_310_507_862_914;
+
+/// Size of the initial simplex in normalized parameter units.
+const INITIAL_STEP: f64 = 0.2;
+
+/// Spread of the distances in the simplex below which the search has converged.
+const TOLERANCE: f64 = 1e-6;
+
+/// Weights of the terms of the distance minimized by [fit_program].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FitWeights {
+    /// Weight of the RMS difference of the octave band decay curves in dB.
+    pub decay: f32,
+    /// Weight of the absolute difference in clarity (C80) in dB.
+    pub early_energy: f32,
+    /// Weight of the RMS difference of the normalized echo density,
+    /// which is about 0 for sparse reflections and 1 for a diffuse tail.
+    pub echo_density: f32,
+}
+
+impl Default for FitWeights {
+    /// Weighs a difference in echo density of 0.1 like a difference of 1 dB.
+    fn default() -> Self {
+        Self {
+            decay: 1.0,
+            early_energy: 1.0,
+            echo_density: 10.0,
+        }
+    }
+}
+
+/// Settings of [fit_program].
+#[derive(Clone, Debug, PartialEq)]
+pub struct FitOptions {
+    /// Program the search starts from.
+    pub initial: Program,
+    /// Parameters to optimize. All others keep their values from `initial`.
+    pub parameters: Vec<ParamId>,
+    /// Maximum number of candidate impulse responses to render.
+    pub max_evaluations: usize,
+    pub weights: FitWeights,
+}
+
+impl Default for FitOptions {
+    /// Optimizes all parameters, starting from [DARK_PLATE], with up to 1000 evaluations.
+    fn default() -> Self {
+        Self {
+            initial: DARK_PLATE,
+            parameters: ParamId::ALL.to_vec(),
+            max_evaluations: 1000,
+            weights: FitWeights::default(),
+        }
+    }
+}
+
+/// The result of [fit_program]: the best program found and how closely it matches the target.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProgramFit {
+    pub program: Program,
+    /// The weighted distance to the target that was minimized, see [FitWeights].
+    pub distance: f32,
+    /// RMS difference of the Schroeder decay curves of the octave bands in dB.
+    pub decay_error_db: f32,
+    /// Clarity (C80) of the fitted response minus that of the target in dB.
+    pub early_energy_error_db: f32,
+    /// RMS difference of the normalized echo density over the first 500 ms.
+    pub echo_density_error: f32,
+    /// Number of candidate impulse responses rendered.
+    pub evaluations: usize,
+    /// Metrics of the target, from its onset, for comparing decay times per band.
+    pub target: AcousticAnalysis,
+    /// Metrics of the impulse response of the fitted program.
+    pub fitted: AcousticAnalysis,
+}
+
+/// Searches for the program whose impulse response best matches a target response,
+/// such as a measured room, with the Nelder–Mead simplex method.
+///
+/// Candidates are rendered with [render_impulse_response] at the target's sample rate,
+/// as long as the target from its onset (the first sample within 20 dB of the peak).
+/// They are compared to the target in terms of the decay curves of the octave bands,
+/// the ratio of early to late energy and the echo density,
+/// which are independent of the level of the target.
+///
+/// Each evaluation renders an impulse response, so the search takes a while for long
+/// targets or many evaluations. It finds a local minimum near `initial`, so starting
+/// from a similar program and optimizing fewer parameters gives better results.
+/// Panics if the target is empty or its sample rate is not supported by
+/// [ReverbController](crate::ReverbController).
+pub fn fit_program(target: &StereoBuffer, options: &FitOptions) -> ProgramFit {
+    assert!(!target.is_empty(), "target impulse response is empty");
+    assert_sample_rate(target.sample_rate);
+    let start = onset(&energy(target));
+    let target = StereoBuffer {
+        sample_rate: target.sample_rate,
+        left: target.left[start..].to_vec(),
+        right: target.right[start..].to_vec(),
+    };
+    let target_features = Features::new(&target);
+
+    let initial = options.initial.to_array();
+    let indices: Vec<usize> = options
+        .parameters
+        .iter()
+        .map(|&id| u8::from(id) as usize)
+        .collect();
+    let program = |x: &[f64]| {
+        let mut values = initial;
+        for (&i, &value) in indices.iter().zip(x) {
+            values[i] = value as f32;
+        }
+        Program::from_array(values)
+    };
+    let render =
+        |program: &Program| render_impulse_response(program, target.sample_rate, target.len());
+
+    let start: Vec<f64> = indices.iter().map(|&i| initial[i] as f64).collect();
+    let (best, evaluations) = nelder_mead(start, options.max_evaluations, |x| {
+        let features = Features::new(&render(&program(x)));
+        features
+            .compare(&target_features, &options.weights)
+            .distance as f64
+    });
+
+    let program = program(&best);
+    let ir = render(&program);
+    let comparison = Features::new(&ir).compare(&target_features, &options.weights);
+    ProgramFit {
+        program,
+        distance: comparison.distance,
+        decay_error_db: comparison.decay_error_db,
+        early_energy_error_db: comparison.early_energy_error_db,
+        echo_density_error: comparison.echo_density_error,
+        evaluations,
+        target: AcousticAnalysis::from_impulse_response(&target),
+        fitted: AcousticAnalysis::from_impulse_response(&ir),
+    }
+}
+
+/// The properties of an impulse response that are compared while fitting.
+struct Features {
+    /// Schroeder decay curve of each octave band below the Nyquist frequency,
+    /// sampled at [DECAY_POINTS] evenly spaced times.
+    decay: Vec<Vec<f32>>,
+    c80: f32,
+    /// Normalized echo density after Abel and Huang,
+    /// sampled at [ECHO_DENSITY_POINTS] evenly spaced times.
+    echo_density: Vec<f32>,
+}
+
+struct Comparison {
+    distance: f32,
+    decay_error_db: f32,
+    early_energy_error_db: f32,
+    echo_density_error: f32,
+}
+
+impl Features {
+    fn new(ir: &StereoBuffer) -> Self {
+        let decay = OCTAVE_BANDS
+            .iter()
+            .filter(|band| band.upper_edge() < ir.sample_rate / 2.0)
+            .map(|band| {
+                let curve = schroeder_curve(&band.filter(ir));
+                (1..=DECAY_POINTS)
+                    // also maps the NaN of a silent band to the floor
+                    .map(|i| curve[i * curve.len() / (DECAY_POINTS + 1)].max(DECAY_FLOOR_DB))
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            decay,
+            c80: AcousticMetrics::from_impulse_response(ir).c80,
+            echo_density: echo_density(ir),
+        }
+    }
+
+    fn compare(&self, target: &Features, weights: &FitWeights) -> Comparison {
+        let decay_error_db = rms(self
+            .decay
+            .iter()
+            .zip(&target.decay)
+            .flat_map(|(a, b)| a.iter().zip(b)));
+        let early_energy_error_db = self.c80 - target.c80;
+        let echo_density_error = rms(self.echo_density.iter().zip(&target.echo_density));
+        let distance = weights.decay * decay_error_db
+            + weights.early_energy * early_energy_error_db.abs()
+            + weights.echo_density * echo_density_error;
+        Comparison {
+            distance,
+            decay_error_db,
+            early_energy_error_db,
+            echo_density_error,
+        }
+    }
+}
+
+/// Returns the root mean square of the differences between pairs of values.
+fn rms<'a>(pairs: impl Iterator<Item = (&'a f32, &'a f32)>) -> f32 {
+    let (sum, count) = pairs.fold((0.0, 0), |(sum, count), (a, b)| {
+        (sum + (a - b).powi(2), count + 1)
+    });
+    (sum / count.max(1) as f32).sqrt()
+}
+
+/// Returns the normalized echo density of the response, summed to mono:
+/// the fraction of samples in a Hann window that lie outside the window's
+/// standard deviation, relative to the fraction for Gaussian noise.
+fn echo_density(ir: &StereoBuffer) -> Vec<f32> {
+    let mono: Vec<f64> = ir
+        .left
+        .iter()
+        .zip(&ir.right)
+        .map(|(&l, &r)| (l as f64 + r as f64) / 2.0)
+        .collect();
+    let span = (ECHO_DENSITY_TIME * ir.sample_rate).min(mono.len() as f32);
+    let half = ((ECHO_DENSITY_WINDOW * ir.sample_rate / 2.0) as isize).max(1);
+    (0..ECHO_DENSITY_POINTS)
+        .map(|point| {
+            let centre = ((point as f32 + 0.5) * span / ECHO_DENSITY_POINTS as f32) as isize;
+            let window: Vec<(f64, f64)> = (-half..=half)
+                .filter_map(|offset| {
+                    let x = *mono.get(usize::try_from(centre + offset).ok()?)?;
+                    let w = 0.5 + 0.5 * (PI * offset as f64 / (half + 1) as f64).cos();
+                    Some((w, x))
+                })
+                .collect();
+            let weight: f64 = window.iter().map(|(w, _)| w).sum();
+            let deviation = (window.iter().map(|(w, x)| w * x * x).sum::<f64>() / weight).sqrt();
+            if deviation == 0.0 {
+                return 0.0;
+            }
+            let outliers: f64 = window
+                .iter()
+                .filter(|(_, x)| x.abs() > deviation)
+                .map(|(w, _)| w)
+                .sum();
+            (outliers / weight / GAUSSIAN_OUTLIERS) as f32
+        })
+        .collect()
+}
+
+/// Minimizes `f` over the unit cube with the Nelder–Mead simplex method,
+/// using the dimension-dependent coefficients of Gao and Han (2012),
+/// which work better for many parameters. Points are clamped to the cube.
+/// Returns the best point found and the number of evaluations of `f`.
+fn nelder_mead(
+    start: Vec<f64>,
+    max_evaluations: usize,
+    mut f: impl FnMut(&[f64]) -> f64,
+) -> (Vec<f64>, usize) {
+    let n = start.len();
+    let mut evaluations = 0;
+    let mut evaluate = |x: &[f64], evaluations: &mut usize| {
+        *evaluations += 1;
+        let value = f(x);
+        // an unmeasurable candidate, e.g. a silent one, is worse than any other
+        if value.is_nan() { f64::INFINITY } else { value }
+    };
+    if n == 0 || max_evaluations == 0 {
+        return (start, 0);
+    }
+
+    let mut simplex = vec![(start.clone(), evaluate(&start, &mut evaluations))];
+    for i in 0..n {
+        if evaluations >= max_evaluations {
+            break;
+        }
+        let mut x = start.clone();
+        x[i] = if x[i] + INITIAL_STEP <= 1.0 {
+            x[i] + INITIAL_STEP
+        } else {
+            x[i] - INITIAL_STEP
+        };
+        let value = evaluate(&x, &mut evaluations);
+        simplex.push((x, value));
+    }
+
+    let dimensions = (n as f64).max(2.0);
+    let expansion = 1.0 + 2.0 / dimensions;
+    let contraction = 0.75 - 1.0 / (2.0 * dimensions);
+    let shrinkage = 1.0 - 1.0 / dimensions;
+    // moves from `from` towards or beyond `to` by `factor`, clamped to the unit cube
+    let step = |from: &[f64], to: &[f64], factor: f64| -> Vec<f64> {
+        from.iter()
+            .zip(to)
+            .map(|(a, b)| (a + factor * (b - a)).clamp(0.0, 1.0))
+            .collect()
+    };
+
+    while simplex.len() == n + 1 && evaluations < max_evaluations {
+        simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let (best, second_worst, worst) = (simplex[0].1, simplex[n - 1].1, simplex[n].1);
+        if worst - best <= TOLERANCE {
+            break;
+        }
+
+        let mut centroid = vec![0.0; n];
+        for (x, _) in &simplex[..n] {
+            for (c, v) in centroid.iter_mut().zip(x) {
+                *c += v / n as f64;
+            }
+        }
+
+        let reflected = step(&simplex[n].0, &centroid, 2.0);
+        let reflected_value = evaluate(&reflected, &mut evaluations);
+        if reflected_value < best {
+            if evaluations < max_evaluations {
+                let expanded = step(&centroid, &reflected, expansion);
+                let expanded_value = evaluate(&expanded, &mut evaluations);
+                if expanded_value < reflected_value {
+                    simplex[n] = (expanded, expanded_value);
+                    continue;
+                }
+            }
+            simplex[n] = (reflected, reflected_value);
+            continue;
+        }
+        if reflected_value < second_worst {
+            simplex[n] = (reflected, reflected_value);
+            continue;
+        }
+        if evaluations >= max_evaluations {
+            if reflected_value < worst {
+                simplex[n] = (reflected, reflected_value);
+            }
+            break;
+        }
+
+        // contract towards the reflected point if it improved on the worst, otherwise inside
+        let outside = reflected_value < worst;
+        let contracted = if outside {
+            step(&centroid, &reflected, contraction)
+        } else {
+            step(&centroid, &simplex[n].0, contraction)
+        };
+        let contracted_value = evaluate(&contracted, &mut evaluations);
+        if contracted_value < reflected_value.min(worst) {
+            simplex[n] = (contracted, contracted_value);
+            continue;
+        }
+
+        // shrink all points towards the best one
+        for i in 1..=n {
+            if evaluations >= max_evaluations {
+                break;
+            }
+            let x = step(&simplex[0].0, &simplex[i].0, shrinkage);
+            let value = evaluate(&x, &mut evaluations);
+            simplex[i] = (x, value);
+        }
+    }
+
+    let (best, _) = simplex
+        .into_iter()
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap();
+    (best, evaluations)
+}
@@ -0,0 +1,75 @@
+use super::render_wet;
+use crate::Program;
+use crate::filter::k_weighting;
+use std::f64::consts::LN_10;
+
+/// Longest part of the impulse response rendered to measure loudness, in seconds.
+/// The energy of longer tails is extrapolated from the late decay time.
+const MEASURED_TIME: f32 = 3.0;
+
+/// Time at the end of a truncated impulse response over which
+/// the energy density is averaged for extrapolating the rest of the tail, in seconds.
+const EXTRAPOLATION_WINDOW: f32 = 0.1;
+
+/// Level below which the remaining tail no longer contributes to the loudness, in dB.
+const DECAYED_DB: f32 = -60.0;
+
+impl Program {
+    /// Returns the gain in dB that brings the perceived level of this program's wet signal,
+    /// its early and late output, to the level of its input at the given sample rate.
+    ///
+    /// Loudness is measured as the energy of the wet impulse response, K-weighted
+    /// as in ITU-R BS.1770 and averaged over both channels, relative to the K-weighted
+    /// energy of the impulse itself. A compensated program is thus as loud as
+    /// white noise played into it, so programs can be compared at equal loudness.
+    ///
+    /// Renders up to 3 seconds of the impulse response
+    /// with [render_impulse_response](crate::render_impulse_response),
+    /// and extrapolates the energy of longer tails from the late decay time,
+    /// which allocates and takes some tens of milliseconds.
+    /// Returns 0 if the wet signal is muted.
+    /// Panics if the sample rate is not supported, like
+    /// [render_impulse_response](crate::render_impulse_response).
+    pub fn loudness_compensation(&self, sample_rate: f32) -> f32 {
+        let max_len = (MEASURED_TIME * sample_rate) as usize;
+        let (ir, tail) = render_wet(self, sample_rate, DECAYED_DB, max_len);
+        let len = ir.len();
+        if len == 0 {
+            return 0.0;
+        }
+
+        let weighted_energy = |channel: &[f32]| -> Vec<f64> {
+            let mut filter = k_weighting(sample_rate as f64);
+            channel
+                .iter()
+                .map(|&x| {
+                    let y = filter.iter_mut().fold(x as f64, |x, f| f.process(x));
+                    y * y
+                })
+                .collect()
+        };
+
+        let energy: Vec<f64> = weighted_energy(&ir.left)
+            .iter()
+            .zip(&weighted_energy(&ir.right))
+            .map(|(l, r)| (l + r) / 2.0)
+            .collect();
+        let mut total: f64 = energy.iter().sum();
+        if len < tail {
+            // the late lines decay exponentially by 60 dB over the decay time,
+            // which leaves the current energy density times decay_time / (6 ln 10)
+            let window = ((EXTRAPOLATION_WINDOW * sample_rate) as usize).clamp(1, len);
+            let density = energy[len - window..].iter().sum::<f64>() / window as f64;
+            let decay_time = self.late_decay_time() as f64 * sample_rate as f64;
+            total += density * decay_time / (6.0 * LN_10);
+        }
+        if total <= 0.0 {
+            return 0.0;
+        }
+
+        let mut impulse = vec![0.0; sample_rate as usize];
+        impulse[0] = 1.0;
+        let reference: f64 = weighted_energy(&impulse).iter().sum();
+        (10.0 * (reference / total).log10()) as f32
+    }
+}
@@ -0,0 +1,270 @@
+use super::fft::{Complex, convolve};
+use crate::{EngineSample, ReverbController, Sample, StereoBuffer};
+use std::f64::consts::PI;
+
+/// A full-scale test signal that is played through a system to measure its impulse response,
+/// the same way hardware reverbs and rooms are measured.
+pub trait Excitation {
+    /// Generates the signal to play at the given sample rate.
+    fn signal(&self, sample_rate: f32) -> Vec<f32>;
+
+    /// Returns the number of frames to record, starting with the signal,
+    /// to measure an impulse response of `ir_len` frames.
+    fn recording_len(&self, sample_rate: f32, ir_len: usize) -> usize;
+
+    /// Recovers the first `ir_len` frames of the impulse response from a recording
+    /// of the response to [signal](Self::signal) that is
+    /// [recording_len](Self::recording_len) frames long.
+    fn deconvolve(&self, recording: &StereoBuffer, ir_len: usize) -> StereoBuffer;
+
+    /// Plays the signal into both inputs of a reverb in its current state,
+    /// followed by silence, and deconvolves the output into an impulse response.
+    ///
+    /// Unlike [render_impulse_response](crate::render_impulse_response),
+    /// this measures the reverb as it runs, with its modulation at steady state
+    /// and any smoothing or crossfades in progress.
+    fn measure<S: EngineSample>(
+        &self,
+        reverb: &mut ReverbController<S>,
+        ir_len: usize,
+    ) -> StereoBuffer {
+        let sample_rate = reverb.get_sample_rate();
+        let len = self.recording_len(sample_rate, ir_len);
+        let mut input: Vec<S> = self
+            .signal(sample_rate)
+            .into_iter()
+            .map(S::from_f32)
+            .collect();
+        input.resize(len, S::default());
+
+        let mut out_l = vec![S::default(); len];
+        let mut out_r = vec![S::default(); len];
+        reverb.process_any_len(&input, &input, &mut out_l, &mut out_r);
+        let recording = StereoBuffer {
+            sample_rate,
+            left: out_l.into_iter().map(Sample::to_f32).collect(),
+            right: out_r.into_iter().map(Sample::to_f32).collect(),
+        };
+        self.deconvolve(&recording, ir_len)
+    }
+}
+
+/// Length of the fades at the start and end of a [SineSweep] in seconds.
+const SWEEP_FADE: f64 = 0.01;
+
+/// An exponential sine sweep after Farina, deconvolved with its inverse filter.
+///
+/// Harmonic distortion of the measured system ends up before the impulse response
+/// and is discarded. The response is only accurate between the start and end frequencies.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SineSweep {
+    /// Start frequency in Hz.
+    pub start_freq: f32,
+    /// End frequency in Hz, which must be below the Nyquist frequency.
+    pub end_freq: f32,
+    /// Duration in seconds, excluding the recorded tail.
+    pub duration: f32,
+}
+
+impl SineSweep {
+    fn len(&self, sample_rate: f32) -> usize {
+        (self.duration * sample_rate).round() as usize
+    }
+
+    fn sweep(&self, sample_rate: f32) -> Vec<f64> {
+        let len = self.len(sample_rate);
+        let (f1, f2) = (self.start_freq as f64, self.end_freq as f64);
+        let rate = (f2 / f1).ln();
+        let duration = len as f64 / sample_rate as f64;
+        let fade = (SWEEP_FADE * sample_rate as f64).min(len as f64 / 2.0);
+        (0..len)
+            .map(|i| {
+                let t = i as f64 / sample_rate as f64;
+                let phase = 2.0 * PI * f1 * duration / rate * ((t * rate / duration).exp() - 1.0);
+                let edge = i.min(len - 1 - i) as f64;
+                let gain = if edge < fade {
+                    0.5 - 0.5 * (PI * edge / fade).cos()
+                } else {
+                    1.0
+                };
+                phase.sin() * gain
+            })
+            .collect()
+    }
+
+    /// Returns the time-reversed sweep with an envelope that compensates
+    /// for the sweep spending more time at low frequencies,
+    /// scaled so that the sweep convolved with it has unit gain.
+    fn inverse_filter(&self, sample_rate: f32) -> Vec<f64> {
+        let sweep = self.sweep(sample_rate);
+        let len = sweep.len();
+        let rate = (self.end_freq as f64 / self.start_freq as f64).ln();
+        let mut inverse: Vec<f64> = sweep
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(i, &x)| x * (-(i as f64) / len as f64 * rate).exp())
+            .collect();
+
+        // measure the gain at the geometric centre of the sweep
+        let centre = (self.start_freq as f64 * self.end_freq as f64).sqrt();
+        let omega = 2.0 * PI * centre / sample_rate as f64;
+        let response = convolve(&sweep, &inverse).iter().enumerate().fold(
+            Complex::default(),
+            |sum, (n, &x)| {
+                let angle = -omega * n as f64;
+                sum + Complex::new(x * angle.cos(), x * angle.sin())
+            },
+        );
+        let gain = response.norm_sqr().sqrt();
+        for x in &mut inverse {
+            *x /= gain;
+        }
+        inverse
+    }
+}
+
+impl Excitation for SineSweep {
+    fn signal(&self, sample_rate: f32) -> Vec<f32> {
+        self.sweep(sample_rate).iter().map(|&x| x as f32).collect()
+    }
+
+    fn recording_len(&self, sample_rate: f32, ir_len: usize) -> usize {
+        self.len(sample_rate) + ir_len
+    }
+
+    fn deconvolve(&self, recording: &StereoBuffer, ir_len: usize) -> StereoBuffer {
+        let inverse = self.inverse_filter(recording.sample_rate);
+        // the impulse response starts where the end of the sweep meets the start of the inverse
+        let offset = inverse.len().saturating_sub(1);
+        let len = recording.len().min(offset + 1 + ir_len);
+        let deconvolve = |channel: &[f32]| -> Vec<f32> {
+            let channel: Vec<f64> = channel[..len].iter().map(|&x| x as f64).collect();
+            let response = convolve(&channel, &inverse);
+            (offset..offset + ir_len)
+                .map(|i| response.get(i).map_or(0.0, |&x| x as f32))
+                .collect()
+        };
+        StereoBuffer {
+            sample_rate: recording.sample_rate,
+            left: deconvolve(&recording.left),
+            right: deconvolve(&recording.right),
+        }
+    }
+}
+
+/// Feedback taps of maximum length linear feedback shift registers, indexed by order,
+/// after Xilinx application note XAPP052.
+const MLS_TAPS: [&[u32]; 25] = [
+    &[],
+    &[],
+    &[2, 1],
+    &[3, 2],
+    &[4, 3],
+    &[5, 3],
+    &[6, 5],
+    &[7, 6],
+    &[8, 6, 5, 4],
+    &[9, 5],
+    &[10, 7],
+    &[11, 9],
+    &[12, 6, 4, 1],
+    &[13, 4, 3, 1],
+    &[14, 5, 3, 1],
+    &[15, 14],
+    &[16, 15, 13, 4],
+    &[17, 14],
+    &[18, 11],
+    &[19, 6, 2, 1],
+    &[20, 17],
+    &[21, 19],
+    &[22, 21],
+    &[23, 18],
+    &[24, 23, 22, 17],
+];
+
+/// A maximum length sequence of `2^order - 1` samples, played `repetitions` times
+/// and deconvolved by circular cross-correlation.
+///
+/// The first period excites the system into a steady state and is discarded,
+/// the following ones are averaged to lower noise.
+/// The impulse response must be shorter than a period, or its tail wraps around
+/// to the start. MLS measurements are sensitive to distortion and time variance
+/// of the measured system, so use a [SineSweep] for programs with modulation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Mls {
+    /// Order in range 2..=24.
+    pub order: u32,
+    /// Number of periods to play, at least 2.
+    pub repetitions: u32,
+}
+
+impl Mls {
+    /// Returns the length of a period.
+    pub fn period(&self) -> usize {
+        (1 << self.order) - 1
+    }
+
+    /// Returns one period of the sequence as ±1 values.
+    pub fn sequence(&self) -> Vec<f32> {
+        assert!(
+            (2..=24).contains(&self.order),
+            "MLS order {} is not in range 2..=24",
+            self.order
+        );
+        let taps = MLS_TAPS[self.order as usize];
+        let mut state: u32 = 1;
+        (0..self.period())
+            .map(|_| {
+                let feedback = taps.iter().fold(0, |bit, &tap| bit ^ (state >> (tap - 1)));
+                let out = (state >> (self.order - 1)) & 1;
+                state = ((state << 1) | (feedback & 1)) & ((1 << self.order) - 1);
+                if out == 1 { 1.0 } else { -1.0 }
+            })
+            .collect()
+    }
+}
+
+impl Excitation for Mls {
+    fn signal(&self, _sample_rate: f32) -> Vec<f32> {
+        let sequence = self.sequence();
+        sequence.repeat(self.repetitions as usize)
+    }
+
+    fn recording_len(&self, _sample_rate: f32, _ir_len: usize) -> usize {
+        self.period() * self.repetitions as usize
+    }
+
+    /// Panics if `ir_len` is longer than a period,
+    /// or the recording has fewer than two periods.
+    fn deconvolve(&self, recording: &StereoBuffer, ir_len: usize) -> StereoBuffer {
+        let period = self.period();
+        assert!(ir_len <= period, "impulse response longer than MLS period");
+        let periods = recording.len() / period;
+        assert!(periods >= 2, "recording is shorter than two MLS periods");
+
+        // two periods of the sequence, reversed, so that convolving a period
+        // of the recording with it gives the circular cross-correlation
+        let sequence: Vec<f64> = self.sequence().iter().map(|&x| x as f64).collect();
+        let reversed: Vec<f64> = sequence.iter().chain(&sequence).rev().copied().collect();
+
+        let deconvolve = |channel: &[f32]| -> Vec<f32> {
+            let mut average = vec![0.0; period];
+            for chunk in channel[period..periods * period].chunks_exact(period) {
+                for (a, &x) in average.iter_mut().zip(chunk) {
+                    *a += x as f64 / (periods - 1) as f64;
+                }
+            }
+            let correlation = convolve(&average, &reversed);
+            correlation[period - 1..period - 1 + ir_len]
+                .iter()
+                .map(|&x| (x / (period + 1) as f64) as f32)
+                .collect()
+        };
+        StereoBuffer {
+            sample_rate: recording.sample_rate,
+            left: deconvolve(&recording.left),
+            right: deconvolve(&recording.right),
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! Objective measurements of impulse responses, see [AcousticAnalysis] and [SpectralAnalysis],
+//! measurement of impulse responses with test signals, see [Excitation],
+//! loudness matching of programs, see [Program::loudness_compensation],
+//! and fitting programs to impulse responses, see [fit_program].
+
+mod decay;
+mod fft;
+mod fit;
+mod loudness;
+mod measurement;
+mod octave;
+mod spectral;
+
+pub use decay::*;
+pub use fit::*;
+pub use measurement::*;
+pub use octave::*;
+pub use spectral::SpectralAnalysis;
+
+use crate::reverb::assert_sample_rate;
+use crate::{DEFAULT_TAIL_THRESHOLD_DB, Program, StereoBuffer, render_impulse_response};
+
+/// Room-acoustic parameters of an impulse response,
+/// both broadband and for each octave band below the Nyquist frequency.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AcousticAnalysis {
+    pub broadband: AcousticMetrics,
+    /// Metrics of each octave band, in the order of [OCTAVE_BANDS].
+    pub octave_bands: Vec<(OctaveBand, AcousticMetrics)>,
+}
+
+impl AcousticAnalysis {
+    /// Analyzes an impulse response, summing the energy of both channels.
+    pub fn from_impulse_response(ir: &StereoBuffer) -> Self {
+        let broadband = AcousticMetrics::from_impulse_response(ir);
+        let octave_bands = OCTAVE_BANDS
+            .iter()
+            .filter(|band| band.upper_edge() < ir.sample_rate / 2.0)
+            .map(|&band| {
+                let filtered = band.filter(ir);
+                (band, AcousticMetrics::from_impulse_response(&filtered))
+            })
+            .collect();
+        Self {
+            broadband,
+            octave_bands,
+        }
+    }
+
+    /// Renders the impulse response of `program` with [render_impulse_response]
+    /// until it decays below [DEFAULT_TAIL_THRESHOLD_DB], and analyzes it.
+    /// The dry signal is left out, so the metrics describe the reverb alone.
+    /// Panics if the sample rate is not supported, like [render_impulse_response].
+    pub fn from_program(program: &Program, sample_rate: f32) -> Self {
+        let (ir, _) = render_wet(program, sample_rate, DEFAULT_TAIL_THRESHOLD_DB, usize::MAX);
+        Self::from_impulse_response(&ir)
+    }
+
+    /// Returns the metrics of the octave band with the given centre frequency in Hz.
+    pub fn band(&self, centre: f32) -> Option<&AcousticMetrics> {
+        self.octave_bands
+            .iter()
+            .find(|(band, _)| band.centre == centre)
+            .map(|(_, metrics)| metrics)
+    }
+}
+
+/// Renders the impulse response of `program` without the dry signal
+/// until it decays below `threshold_db`, but at most `max_len` frames long.
+/// Also returns the estimated length of the whole tail in frames.
+fn render_wet(
+    program: &Program,
+    sample_rate: f32,
+    threshold_db: f32,
+    max_len: usize,
+) -> (StereoBuffer, usize) {
+    // checked up front, since muted programs render nothing
+    assert_sample_rate(sample_rate);
+    let program = Program {
+        dry_out: 0.0,
+        ..*program
+    };
+    let tail = program.estimated_tail(sample_rate, threshold_db) as usize;
+    let len = tail.min(max_len);
+    if len == 0 {
+        // a muted program needs no reverb to be created
+        return (StereoBuffer::new(sample_rate, 0), tail);
+    }
+    (render_impulse_response(&program, sample_rate, len), tail)
+}
@@ -0,0 +1,66 @@
+use crate::StereoBuffer;
+use crate::filter::Biquad;
+use std::f64::consts::SQRT_2;
+
+/// An octave band, identified by its nominal centre frequency in Hz.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OctaveBand {
+    pub centre: f32,
+}
+
+/// The octave bands of IEC 61260 from 63 Hz to 16 kHz.
+pub const OCTAVE_BANDS: [OctaveBand; 9] = [
+    OctaveBand { centre: 63.0 },
+    OctaveBand { centre: 125.0 },
+    OctaveBand { centre: 250.0 },
+    OctaveBand { centre: 500.0 },
+    OctaveBand { centre: 1000.0 },
+    OctaveBand { centre: 2000.0 },
+    OctaveBand { centre: 4000.0 },
+    OctaveBand { centre: 8000.0 },
+    OctaveBand { centre: 16000.0 },
+];
+
+/// Q factors of the two sections of a 4th order Butterworth filter.
+const BUTTERWORTH_Q: [f64; 2] = [0.541_196_1, 1.306_563];
+
+impl OctaveBand {
+    /// Returns the lower -3 dB frequency in Hz.
+    pub fn lower_edge(&self) -> f32 {
+        self.centre / SQRT_2 as f32
+    }
+
+    /// Returns the upper -3 dB frequency in Hz.
+    pub fn upper_edge(&self) -> f32 {
+        self.centre * SQRT_2 as f32
+    }
+
+    /// Returns a copy of the signal filtered to this band, with a 4th order
+    /// Butterworth highpass at the lower edge and lowpass at the upper edge.
+    /// The upper edge must be below the Nyquist frequency.
+    pub fn filter(&self, signal: &StereoBuffer) -> StereoBuffer {
+        let sample_rate = signal.sample_rate as f64;
+        let filter = |channel: &[f32]| {
+            let mut sections = BUTTERWORTH_Q
+                .map(|q| Biquad::highpass(self.lower_edge() as f64, q, sample_rate))
+                .into_iter()
+                .chain(
+                    BUTTERWORTH_Q
+                        .map(|q| Biquad::lowpass(self.upper_edge() as f64, q, sample_rate)),
+                )
+                .collect::<Vec<_>>();
+            channel
+                .iter()
+                .map(|&x| {
+                    let y = sections.iter_mut().fold(x as f64, |x, s| s.process(x));
+                    y as f32
+                })
+                .collect()
+        };
+        StereoBuffer {
+            sample_rate: signal.sample_rate,
+            left: filter(&signal.left),
+            right: filter(&signal.right),
+        }
+    }
+}
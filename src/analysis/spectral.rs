@@ -0,0 +1,132 @@
+use super::decay::decay_slope;
+use super::fft::{Complex, fft};
+use super::render_wet;
+use crate::{DEFAULT_TAIL_THRESHOLD_DB, Program, StereoBuffer};
+use std::f64::consts::PI;
+
+/// How the coloration of an impulse response evolves over time,
+/// computed from a short-time Fourier transform with a Hann window
+/// and a hop size of a quarter of the FFT size.
+///
+/// Levels are energy summed over both channels, in dB relative to a flat response,
+/// so a unit impulse has a magnitude response of 0 dB at all frequencies.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpectralAnalysis {
+    pub sample_rate: f32,
+    /// Centre frequency of each band in Hz, from 0 up to the Nyquist frequency.
+    pub frequencies: Vec<f32>,
+    /// Magnitude response in dB for each band,
+    /// smoothed to the resolution of the FFT size.
+    pub magnitude_db: Vec<f32>,
+    /// Centre time of each frame in seconds.
+    /// The first frames are centred before the start of the response,
+    /// so that every sample is covered by the same number of frames.
+    pub times: Vec<f32>,
+    /// Energy decay relief in dB, indexed by frame and band:
+    /// the energy remaining in each band from each frame to the end.
+    /// The first frame equals the magnitude response.
+    pub energy_decay_relief: Vec<Vec<f32>>,
+    /// Reverberation time of each band in seconds,
+    /// extrapolated from its decay from -5 to -25 dB,
+    /// or `None` if the band doesn't decay that far.
+    pub rt60: Vec<Option<f32>>,
+}
+
+impl SpectralAnalysis {
+    /// Analyzes an impulse response with the given FFT size,
+    /// trading time resolution for frequency resolution.
+    /// Panics if `fft_size` is not a power of two of at least 4.
+    pub fn from_impulse_response(ir: &StereoBuffer, fft_size: usize) -> Self {
+        assert!(
+            fft_size.is_power_of_two() && fft_size >= 4,
+            "FFT size {fft_size} is not a power of two of at least 4"
+        );
+        let hop = fft_size / 4;
+        let bands = fft_size / 2 + 1;
+        let window: Vec<f64> = (0..fft_size)
+            .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f64 / fft_size as f64).cos())
+            .collect();
+        // each sample contributes to fft_size / hop frames with the squared window as weight
+        let scale = hop as f64 / window.iter().map(|w| w * w).sum::<f64>();
+
+        // the first frame overlaps the response by a hop, so the first sample gets full coverage
+        let first = -((fft_size - hop) as isize);
+        let num_frames = (ir.len() + fft_size - hop).div_ceil(hop);
+        let mut buf = vec![Complex::default(); fft_size];
+        let mut frame_energy: Vec<Vec<f64>> = (0..num_frames)
+            .map(|frame| {
+                let start = first + (frame * hop) as isize;
+                let mut energy = vec![0.0; bands];
+                for channel in [&ir.left, &ir.right] {
+                    for (i, (c, w)) in buf.iter_mut().zip(&window).enumerate() {
+                        let sample = usize::try_from(start + i as isize)
+                            .ok()
+                            .and_then(|n| channel.get(n));
+                        *c = Complex::new(sample.map_or(0.0, |&x| x as f64) * w, 0.0);
+                    }
+                    fft(&mut buf);
+                    for (e, c) in energy.iter_mut().zip(&buf) {
+                        *e += c.norm_sqr() * scale;
+                    }
+                }
+                energy
+            })
+            .collect();
+
+        // integrate backwards to get the energy remaining from each frame on
+        for frame in (0..num_frames.saturating_sub(1)).rev() {
+            let (current, later) = frame_energy.split_at_mut(frame + 1);
+            for (e, &l) in current[frame].iter_mut().zip(&later[0]) {
+                *e += l;
+            }
+        }
+        let db = |e: f64| 10.0 * e.log10();
+
+        let frames_per_second = ir.sample_rate as f64 / hop as f64;
+        let rt60 = (0..bands)
+            .map(|band| {
+                let start = db(frame_energy[0][band]);
+                let curve: Vec<f64> = frame_energy.iter().map(|f| db(f[band]) - start).collect();
+                let per_frame = decay_slope(&curve, -5.0, -25.0)?;
+                Some((-60.0 / (per_frame * frames_per_second)) as f32)
+            })
+            .collect();
+
+        let energy_decay_relief: Vec<Vec<f32>> = frame_energy
+            .iter()
+            .map(|energy| energy.iter().map(|&e| db(e) as f32).collect())
+            .collect();
+        Self {
+            sample_rate: ir.sample_rate,
+            frequencies: (0..bands)
+                .map(|band| band as f32 * ir.sample_rate / fft_size as f32)
+                .collect(),
+            magnitude_db: energy_decay_relief[0].clone(),
+            times: (0..num_frames)
+                .map(|frame| {
+                    let centre = first + (frame * hop + fft_size / 2) as isize;
+                    centre as f32 / ir.sample_rate
+                })
+                .collect(),
+            energy_decay_relief,
+            rt60,
+        }
+    }
+
+    /// Renders the impulse response of `program`
+    /// with [render_impulse_response](crate::render_impulse_response)
+    /// until it decays below [DEFAULT_TAIL_THRESHOLD_DB], and analyzes it.
+    /// The dry signal is left out, so the analysis shows the coloration of the reverb alone.
+    /// Panics if the sample rate is not supported, like
+    /// [render_impulse_response](crate::render_impulse_response).
+    pub fn from_program(program: &Program, sample_rate: f32, fft_size: usize) -> Self {
+        let (ir, _) = render_wet(program, sample_rate, DEFAULT_TAIL_THRESHOLD_DB, usize::MAX);
+        Self::from_impulse_response(&ir, fft_size)
+    }
+
+    /// Returns the index of the band closest to the given frequency in Hz.
+    pub fn band(&self, frequency: f32) -> usize {
+        let width = self.sample_rate / 2.0 / (self.frequencies.len() - 1) as f32;
+        ((frequency / width).round().max(0.0) as usize).min(self.frequencies.len() - 1)
+    }
+}
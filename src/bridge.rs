@@ -8,12 +8,22 @@ mod ffi {
         include!("cloudseed_bridge.h");
 
         type CloudSeedReverb;
+        type CloudSeedReverbF64;
 
-        fn cs_new_reverb(sample_rate: f32, max_block: u32) -> UniquePtr<CloudSeedReverb>;
+        fn cs_new_reverb(
+            sample_rate: f32,
+            max_block: u32,
+            seed: u64,
+        ) -> Result<UniquePtr<CloudSeedReverb>>;
+        fn cs_new_reverb_f64(
+            sample_rate: f32,
+            max_block: u32,
+            seed: u64,
+        ) -> Result<UniquePtr<CloudSeedReverbF64>>;
         fn cs_format_parameter(param_id: u32, value: f32) -> String;
 
         fn reset(self: Pin<&mut CloudSeedReverb>);
-        fn set_sample_rate(self: Pin<&mut CloudSeedReverb>, sample_rate: f32);
+        fn set_sample_rate(self: Pin<&mut CloudSeedReverb>, sample_rate: f32) -> Result<()>;
         fn get_sample_rate(self: &CloudSeedReverb) -> f32;
 
         fn set_parameter(self: Pin<&mut CloudSeedReverb>, param_id: u32, value: f32);
@@ -22,6 +32,17 @@ mod ffi {
         fn get_all_parameters(self: &CloudSeedReverb, out: &mut [f32]);
         fn load_program(self: Pin<&mut CloudSeedReverb>, params: &[f32]);
 
+        fn set_freeze(self: Pin<&mut CloudSeedReverb>, enabled: bool);
+        fn set_wet_gain(self: Pin<&mut CloudSeedReverb>, gain: f32);
+        fn set_mono_dry(self: Pin<&mut CloudSeedReverb>, enabled: bool);
+
+        fn memory_usage(self: &CloudSeedReverb) -> usize;
+        fn propagation_samples(self: &CloudSeedReverb) -> u32;
+
+        fn state_size(self: &CloudSeedReverb) -> usize;
+        fn save_state(self: &CloudSeedReverb, out: &mut [u8]);
+        fn load_state(self: Pin<&mut CloudSeedReverb>, params: &[f32], state: &[u8]) -> bool;
+
         fn process(
             self: Pin<&mut CloudSeedReverb>,
             in_l: &[f32],
@@ -30,6 +51,69 @@ mod ffi {
             out_r: &mut [f32],
             frames: u32,
         );
+
+        #[allow(clippy::too_many_arguments)]
+        fn process_buses(
+            self: Pin<&mut CloudSeedReverb>,
+            in_l: &[f32],
+            in_r: &[f32],
+            out_l: &mut [f32],
+            out_r: &mut [f32],
+            dry_l: &mut [f32],
+            dry_r: &mut [f32],
+            early_l: &mut [f32],
+            early_r: &mut [f32],
+            late_l: &mut [f32],
+            late_r: &mut [f32],
+            frames: u32,
+        );
+
+        // the same engine processing f64 samples, see CloudSeedReverbF64 in cloudseed_bridge.h
+        fn reset(self: Pin<&mut CloudSeedReverbF64>);
+        fn set_sample_rate(self: Pin<&mut CloudSeedReverbF64>, sample_rate: f32) -> Result<()>;
+        fn get_sample_rate(self: &CloudSeedReverbF64) -> f32;
+
+        fn set_parameter(self: Pin<&mut CloudSeedReverbF64>, param_id: u32, value: f32);
+        fn get_parameter(self: &CloudSeedReverbF64, param_id: u32) -> f32;
+
+        fn get_all_parameters(self: &CloudSeedReverbF64, out: &mut [f32]);
+        fn load_program(self: Pin<&mut CloudSeedReverbF64>, params: &[f32]);
+
+        fn set_freeze(self: Pin<&mut CloudSeedReverbF64>, enabled: bool);
+        fn set_wet_gain(self: Pin<&mut CloudSeedReverbF64>, gain: f32);
+        fn set_mono_dry(self: Pin<&mut CloudSeedReverbF64>, enabled: bool);
+
+        fn memory_usage(self: &CloudSeedReverbF64) -> usize;
+        fn propagation_samples(self: &CloudSeedReverbF64) -> u32;
+
+        fn state_size(self: &CloudSeedReverbF64) -> usize;
+        fn save_state(self: &CloudSeedReverbF64, out: &mut [u8]);
+        fn load_state(self: Pin<&mut CloudSeedReverbF64>, params: &[f32], state: &[u8]) -> bool;
+
+        fn process(
+            self: Pin<&mut CloudSeedReverbF64>,
+            in_l: &[f64],
+            in_r: &[f64],
+            out_l: &mut [f64],
+            out_r: &mut [f64],
+            frames: u32,
+        );
+
+        #[allow(clippy::too_many_arguments)]
+        fn process_buses(
+            self: Pin<&mut CloudSeedReverbF64>,
+            in_l: &[f64],
+            in_r: &[f64],
+            out_l: &mut [f64],
+            out_r: &mut [f64],
+            dry_l: &mut [f64],
+            dry_r: &mut [f64],
+            early_l: &mut [f64],
+            early_r: &mut [f64],
+            late_l: &mut [f64],
+            late_r: &mut [f64],
+            frames: u32,
+        );
     }
 }
 
@@ -0,0 +1,234 @@
+/// A sample format that can be converted to and from the `f32` or `f64` samples the reverb processes.
+/// Integer samples are scaled to range -1..1, and saturate when converted back.
+pub trait Sample: Copy {
+    /// Converts the sample to `f32`.
+    fn to_f32(self) -> f32;
+    /// Converts an `f32` sample to this format.
+    fn from_f32(value: f32) -> Self;
+    /// Converts the sample to `f64`.
+    fn to_f64(self) -> f64;
+    /// Converts an `f64` sample to this format.
+    fn from_f64(value: f64) -> Self;
+}
+
+impl Sample for f32 {
+    fn to_f32(self) -> f32 {
+        self
+    }
+
+    fn from_f32(value: f32) -> Self {
+        value
+    }
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+}
+
+impl Sample for f64 {
+    fn to_f32(self) -> f32 {
+        self as f32
+    }
+
+    fn from_f32(value: f32) -> Self {
+        value as f64
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+}
+
+impl Sample for i16 {
+    fn to_f32(self) -> f32 {
+        self as f32 / 32_768.0
+    }
+
+    fn from_f32(value: f32) -> Self {
+        // float to int casts saturate, and map NaN to 0
+        (value * 32_768.0).round() as i16
+    }
+
+    fn to_f64(self) -> f64 {
+        self as f64 / 32_768.0
+    }
+
+    fn from_f64(value: f64) -> Self {
+        (value * 32_768.0).round() as i16
+    }
+}
+
+impl Sample for i32 {
+    fn to_f32(self) -> f32 {
+        (self as f64 / 2_147_483_648.0) as f32
+    }
+
+    fn from_f32(value: f32) -> Self {
+        (value as f64 * 2_147_483_648.0).round() as i32
+    }
+
+    fn to_f64(self) -> f64 {
+        self as f64 / 2_147_483_648.0
+    }
+
+    fn from_f64(value: f64) -> Self {
+        (value * 2_147_483_648.0).round() as i32
+    }
+}
+
+/// Converts a sample between two formats.
+/// Going through `f64` gives the same results as the `f32` conversions when either format is `f32`.
+fn convert<S: Sample, T: Sample>(sample: S) -> T {
+    T::from_f64(sample.to_f64())
+}
+
+/// Stereo audio that can be read by [ReverbController::process_buffers](crate::ReverbController::process_buffers).
+///
+/// Implemented for [Interleaved] buffers and for arrays and slices of two channel buffers,
+/// such as `[&[f32]; 2]` or `&[&[i16]]`, for all [Sample] types.
+pub trait InputBuffer {
+    /// Returns the number of stereo frames.
+    fn frames(&self) -> usize;
+
+    /// Converts the frames starting at `start` into the given planar buffers,
+    /// filling them completely.
+    fn read_frames<T: Sample>(&self, start: usize, left: &mut [T], right: &mut [T]);
+}
+
+/// Stereo audio that can be written by [ReverbController::process_buffers](crate::ReverbController::process_buffers).
+///
+/// Implemented for [Interleaved] buffers and for arrays and slices of two mutable channel buffers,
+/// such as `[&mut [f32]; 2]` or `&mut [&mut [i16]]`, for all [Sample] types.
+pub trait OutputBuffer {
+    /// Returns the number of stereo frames.
+    fn frames(&self) -> usize;
+
+    /// Converts the given planar buffers into the frames starting at `start`.
+    fn write_frames<T: Sample>(&mut self, start: usize, left: &[T], right: &[T]);
+}
+
+/// Interleaved stereo frames, i.e. left and right samples alternating.
+/// The wrapped buffer must have an even length.
+pub struct Interleaved<B>(pub B);
+
+/// Returns the number of frames of an interleaved stereo buffer.
+fn interleaved_frames<S>(samples: &[S]) -> usize {
+    assert!(
+        samples.len() % 2 == 0,
+        "interleaved stereo buffer has odd length"
+    );
+    samples.len() / 2
+}
+
+impl<S: Sample> InputBuffer for Interleaved<&[S]> {
+    fn frames(&self) -> usize {
+        interleaved_frames(self.0)
+    }
+
+    fn read_frames<T: Sample>(&self, start: usize, left: &mut [T], right: &mut [T]) {
+        let frames = self.0[start * 2..].chunks_exact(2);
+        for ((frame, l), r) in frames.zip(left).zip(right) {
+            *l = convert(frame[0]);
+            *r = convert(frame[1]);
+        }
+    }
+}
+
+impl<S: Sample> InputBuffer for Interleaved<&mut [S]> {
+    fn frames(&self) -> usize {
+        Interleaved(&*self.0).frames()
+    }
+
+    fn read_frames<T: Sample>(&self, start: usize, left: &mut [T], right: &mut [T]) {
+        Interleaved(&*self.0).read_frames(start, left, right)
+    }
+}
+
+impl<S: Sample> OutputBuffer for Interleaved<&mut [S]> {
+    fn frames(&self) -> usize {
+        interleaved_frames(self.0)
+    }
+
+    fn write_frames<T: Sample>(&mut self, start: usize, left: &[T], right: &[T]) {
+        let frames = self.0[start * 2..].chunks_exact_mut(2);
+        for ((frame, &l), &r) in frames.zip(left).zip(right) {
+            frame[0] = convert(l);
+            frame[1] = convert(r);
+        }
+    }
+}
+
+/// Returns the number of frames of a pair of channel buffers, which must have the same length.
+fn channel_frames<S>(channels: &[impl AsRef<[S]>]) -> usize {
+    assert!(
+        channels.len() == 2,
+        "expected 2 channels, got {}",
+        channels.len()
+    );
+    let len = channels[0].as_ref().len();
+    assert!(
+        channels[1].as_ref().len() == len,
+        "channel buffers have different lengths"
+    );
+    len
+}
+
+fn read_channel<S: Sample, T: Sample>(channel: &[S], start: usize, out: &mut [T]) {
+    for (o, &s) in out.iter_mut().zip(&channel[start..]) {
+        *o = convert(s);
+    }
+}
+
+fn write_channel<S: Sample, T: Sample>(channel: &mut [S], start: usize, input: &[T]) {
+    for (s, &i) in channel[start..].iter_mut().zip(input) {
+        *s = convert(i);
+    }
+}
+
+macro_rules! impl_channel_buffers {
+    ($($ty:ty),*) => {$(
+        impl<S: Sample> InputBuffer for $ty {
+            fn frames(&self) -> usize {
+                channel_frames(&self[..])
+            }
+
+            fn read_frames<T: Sample>(&self, start: usize, left: &mut [T], right: &mut [T]) {
+                read_channel(&self[0], start, left);
+                read_channel(&self[1], start, right);
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_mut_channel_buffers {
+    ($($ty:ty),*) => {$(
+        impl<S: Sample> OutputBuffer for $ty {
+            fn frames(&self) -> usize {
+                channel_frames(&self[..])
+            }
+
+            fn write_frames<T: Sample>(&mut self, start: usize, left: &[T], right: &[T]) {
+                write_channel(&mut self[0], start, left);
+                write_channel(&mut self[1], start, right);
+            }
+        }
+    )*};
+}
+
+impl_channel_buffers!(
+    [&[S]; 2],
+    [&[S]],
+    [&mut [S]; 2],
+    [&mut [S]],
+    [Vec<S>; 2],
+    [Vec<S>]
+);
+impl_mut_channel_buffers!([&mut [S]; 2], [&mut [S]], [Vec<S>; 2], [Vec<S>]);
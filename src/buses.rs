@@ -0,0 +1,30 @@
+/// Buffers receiving the individual signal components of the reverb.
+/// See [ReverbController::process_buses](crate::ReverbController::process_buses).
+///
+/// All signals are captured before the output levels
+/// (`DryOut`, `EarlyOut` and `LateOut`) are applied.
+/// `S` is the sample type of the reverb, see [EngineSample](crate::EngineSample).
+pub struct ReverbBuses<'a, S = f32> {
+    /// The input signal after the `InputMix` cross-feed.
+    pub dry_l: &'a mut [S],
+    pub dry_r: &'a mut [S],
+    /// The early reflections, i.e. the output of the multitap delay and early diffuser.
+    pub early_l: &'a mut [S],
+    pub early_r: &'a mut [S],
+    /// The late reverb tail, i.e. the normalized sum of the delay lines.
+    pub late_l: &'a mut [S],
+    pub late_r: &'a mut [S],
+}
+
+impl<S> ReverbBuses<'_, S> {
+    /// Returns whether all buffers are at least `num_samples` long.
+    pub(crate) fn fits(&self, num_samples: u32) -> bool {
+        let n = num_samples as usize;
+        self.dry_l.len() >= n
+            && self.dry_r.len() >= n
+            && self.early_l.len() >= n
+            && self.early_r.len() >= n
+            && self.late_l.len() >= n
+            && self.late_r.len() >= n
+    }
+}
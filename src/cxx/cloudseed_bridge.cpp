@@ -7,39 +7,51 @@
 
 using Cloudseed::ReverbController;
 
-CloudSeedReverb::CloudSeedReverb(float sample_rate, uint32_t max_block)
-    : controller(new ReverbController(static_cast<int>(sample_rate))),
-      sample_rate(sample_rate), max_block(max_block) {}
+template<typename T>
+CloudSeedReverbT<T>::CloudSeedReverbT(float sample_rate, uint32_t max_block, uint64_t seed)
+    : controller(new ReverbController<T>(static_cast<int>(sample_rate))),
+      sample_rate(sample_rate), max_block(max_block) {
+    controller->SeedModulation(seed);
+}
 
-CloudSeedReverb::~CloudSeedReverb() {
+template<typename T>
+CloudSeedReverbT<T>::~CloudSeedReverbT() {
     delete controller;
 }
 
-void CloudSeedReverb::reset() {
+template<typename T>
+void CloudSeedReverbT<T>::reset() {
     controller->ClearBuffers();
 }
 
-void CloudSeedReverb::set_sample_rate(float sample_rate) {
-    this->sample_rate = sample_rate;
+template<typename T>
+void CloudSeedReverbT<T>::set_sample_rate(float sample_rate) {
+    // SetSamplerate throws if the delay buffers can't be resized,
+    // so only report the new rate once the engine accepted it
     controller->SetSamplerate(static_cast<int>(sample_rate));
+    this->sample_rate = sample_rate;
 }
 
-float CloudSeedReverb::get_sample_rate() const {
+template<typename T>
+float CloudSeedReverbT<T>::get_sample_rate() const {
     return this->sample_rate;
 }
 
-void CloudSeedReverb::set_parameter(uint32_t id, float value) {
+template<typename T>
+void CloudSeedReverbT<T>::set_parameter(uint32_t id, float value) {
     int pid = static_cast<int>(id);
     if (pid >= 0 && pid < Cloudseed::Parameter::COUNT) {
         controller->SetParameter(pid, static_cast<double>(value));
     }
 }
 
-float CloudSeedReverb::get_parameter(uint32_t id) const {
+template<typename T>
+float CloudSeedReverbT<T>::get_parameter(uint32_t id) const {
     return static_cast<float>(controller->GetAllParameters()[id]);
 }
 
-void CloudSeedReverb::get_all_parameters(rust::Slice<float> out) const {
+template<typename T>
+void CloudSeedReverbT<T>::get_all_parameters(rust::Slice<float> out) const {
     int count = Cloudseed::Parameter::COUNT;
     int n = std::min(static_cast<int>(out.size()), count);
     float* src = controller->GetAllParameters();
@@ -48,7 +60,8 @@ void CloudSeedReverb::get_all_parameters(rust::Slice<float> out) const {
     }
 }
 
-void CloudSeedReverb::load_program(rust::Slice<const float> params) {
+template<typename T>
+void CloudSeedReverbT<T>::load_program(rust::Slice<const float> params) {
     int count = Cloudseed::Parameter::COUNT;
 
     // ensure all parameters are provided
@@ -65,22 +78,122 @@ void CloudSeedReverb::load_program(rust::Slice<const float> params) {
     controller->ClearBuffers();
 }
 
-void CloudSeedReverb::process(rust::Slice<const float> in_l,
-                              rust::Slice<const float> in_r,
-                              rust::Slice<float> out_l,
-                              rust::Slice<float> out_r,
-                              uint32_t frames) {
-    // CloudSeedCore expects non-const float* for the input channels,
+template<typename T>
+void CloudSeedReverbT<T>::set_freeze(bool enabled) {
+    controller->SetFreeze(enabled);
+}
+
+template<typename T>
+void CloudSeedReverbT<T>::set_wet_gain(float gain) {
+    controller->SetWetGain(gain);
+}
+
+template<typename T>
+void CloudSeedReverbT<T>::set_mono_dry(bool enabled) {
+    controller->SetMonoDry(enabled);
+}
+
+template<typename T>
+size_t CloudSeedReverbT<T>::memory_usage() const {
+    return sizeof(CloudSeedReverbT) + controller->GetMemoryUsage();
+}
+
+template<typename T>
+uint32_t CloudSeedReverbT<T>::propagation_samples() const {
+    return static_cast<uint32_t>(controller->GetPropagationSamples());
+}
+
+template<typename T>
+size_t CloudSeedReverbT<T>::state_size() const {
+    Cloudseed::StateSizer sizer;
+    controller->SerializeState(sizer);
+    return sizer.Size;
+}
+
+template<typename T>
+void CloudSeedReverbT<T>::save_state(rust::Slice<uint8_t> out) const {
+    if (out.size() != state_size()) {
+        return;
+    }
+    Cloudseed::StateWriter writer(out.data());
+    controller->SerializeState(writer);
+}
+
+template<typename T>
+bool CloudSeedReverbT<T>::load_state(rust::Slice<const float> params, rust::Slice<const uint8_t> state) {
+    int count = Cloudseed::Parameter::COUNT;
+    if (static_cast<int>(params.size()) != count || state.size() != state_size()) {
+        return false;
+    }
+    for (float value : params) {
+        // also rejects NaN
+        if (!(value >= 0.0f && value <= 1.0f)) {
+            return false;
+        }
+    }
+
+    float previous[Cloudseed::Parameter::COUNT];
+    std::copy(controller->GetAllParameters(), controller->GetAllParameters() + count, previous);
+
+    // derived configuration such as delay lengths bounds some of the state,
+    // so the parameters have to be applied before the state can be validated
+    load_program(params);
+
+    Cloudseed::StateReader check(state.data(), false);
+    controller->SerializeState(check);
+    if (!check.Valid) {
+        load_program(rust::Slice<const float>(previous, count));
+        return false;
+    }
+
+    Cloudseed::StateReader reader(state.data(), true);
+    controller->SerializeState(reader);
+    return true;
+}
+
+template<typename T>
+void CloudSeedReverbT<T>::process(rust::Slice<const T> in_l,
+                                  rust::Slice<const T> in_r,
+                                  rust::Slice<T> out_l,
+                                  rust::Slice<T> out_r,
+                                  uint32_t frames) {
+    // CloudSeedCore expects non-const pointers for the input channels,
     // but never actually modifies them. therefore, this const_cast is safe.
-    float* inL = const_cast<float*>(in_l.data());
-    float* inR = const_cast<float*>(in_r.data());
-    float* outL = out_l.data();
-    float* outR = out_r.data();
+    T* inL = const_cast<T*>(in_l.data());
+    T* inR = const_cast<T*>(in_r.data());
+    T* outL = out_l.data();
+    T* outR = out_r.data();
 
     int n = static_cast<int>(frames);
     controller->Process(inL, inR, outL, outR, n);
 }
 
+template<typename T>
+void CloudSeedReverbT<T>::process_buses(rust::Slice<const T> in_l,
+                                        rust::Slice<const T> in_r,
+                                        rust::Slice<T> out_l,
+                                        rust::Slice<T> out_r,
+                                        rust::Slice<T> dry_l,
+                                        rust::Slice<T> dry_r,
+                                        rust::Slice<T> early_l,
+                                        rust::Slice<T> early_r,
+                                        rust::Slice<T> late_l,
+                                        rust::Slice<T> late_r,
+                                        uint32_t frames) {
+    // see process() for why this const_cast is safe
+    T* inL = const_cast<T*>(in_l.data());
+    T* inR = const_cast<T*>(in_r.data());
+
+    int n = static_cast<int>(frames);
+    controller->ProcessBuses(inL, inR, out_l.data(), out_r.data(),
+                             dry_l.data(), dry_r.data(),
+                             early_l.data(), early_r.data(),
+                             late_l.data(), late_r.data(), n);
+}
+
+template class CloudSeedReverbT<float>;
+template class CloudSeedReverbT<double>;
+
 rust::String cs_format_parameter(uint32_t param_id, float value) {
     char buffer[MAX_STR_SIZE];
     Cloudseed::FormatParameter(value, MAX_STR_SIZE, static_cast<int>(param_id), buffer);
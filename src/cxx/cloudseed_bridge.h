@@ -5,13 +5,15 @@
 #include <memory>
 #include "rust/cxx.h"
 
-namespace Cloudseed { class ReverbController; }
+namespace Cloudseed { template<typename T> class ReverbController; }
 
-// Wrapper class for ReverbController's ReverbController, exposed to Rust via cxx
-class CloudSeedReverb {
+// Wrapper class for ReverbController's ReverbController, exposed to Rust via cxx.
+// T is the sample type, float or double; the parameters are always float.
+template<typename T>
+class CloudSeedReverbT {
 public:
-    CloudSeedReverb(float sample_rate, uint32_t max_block);
-    ~CloudSeedReverb();
+    CloudSeedReverbT(float sample_rate, uint32_t max_block, uint64_t seed);
+    ~CloudSeedReverbT();
 
     void reset();
     void set_sample_rate(float sample_rate);
@@ -23,22 +25,68 @@ public:
 
     void load_program(rust::Slice<const float> params);
 
-    void process(rust::Slice<const float> in_l,
-                 rust::Slice<const float> in_r,
-                 rust::Slice<float> out_l,
-                 rust::Slice<float> out_r,
+    void set_freeze(bool enabled);
+
+    /** Sets a linear gain applied to the early and late output, which program loads leave unchanged. */
+    void set_wet_gain(float gain);
+
+    /** Takes the dry signal of both channels from the left input, see ReverbController::SetMonoDry. */
+    void set_mono_dry(bool enabled);
+
+    /** Returns the memory in bytes used by this reverb, including its delay buffers. */
+    size_t memory_usage() const;
+
+    /** Returns an upper bound for the time in samples the input takes to reach the output. */
+    uint32_t propagation_samples() const;
+
+    /** Returns the size in bytes of the running DSP state, excluding parameters. */
+    size_t state_size() const;
+    /** Writes the running DSP state to out, which must be exactly state_size() bytes long. */
+    void save_state(rust::Slice<uint8_t> out) const;
+    /**
+     * Loads the given parameters, then the running DSP state written by save_state.
+     * Returns false without changing anything if the parameters or state are invalid.
+     * If the state turns out to be inconsistent with the parameters, the previous
+     * parameters are restored, the buffers are cleared and false is returned.
+     */
+    bool load_state(rust::Slice<const float> params, rust::Slice<const uint8_t> state);
+
+    void process(rust::Slice<const T> in_l,
+                 rust::Slice<const T> in_r,
+                 rust::Slice<T> out_l,
+                 rust::Slice<T> out_r,
                  uint32_t frames);
 
+    void process_buses(rust::Slice<const T> in_l,
+                       rust::Slice<const T> in_r,
+                       rust::Slice<T> out_l,
+                       rust::Slice<T> out_r,
+                       rust::Slice<T> dry_l,
+                       rust::Slice<T> dry_r,
+                       rust::Slice<T> early_l,
+                       rust::Slice<T> early_r,
+                       rust::Slice<T> late_l,
+                       rust::Slice<T> late_r,
+                       uint32_t frames);
+
 private:
     /** The underlying reverb controller. */
-    Cloudseed::ReverbController* controller = nullptr;
+    Cloudseed::ReverbController<T>* controller = nullptr;
 
     float sample_rate = 48000.0f;
     uint32_t max_block = 0;
 };
 
-inline std::unique_ptr<CloudSeedReverb> cs_new_reverb(float sample_rate, uint32_t max_block) {
-    return std::unique_ptr<CloudSeedReverb>(new CloudSeedReverb(sample_rate, max_block));
+// the instantiations are in cloudseed_bridge.cpp
+using CloudSeedReverb = CloudSeedReverbT<float>;
+using CloudSeedReverbF64 = CloudSeedReverbT<double>;
+
+inline std::unique_ptr<CloudSeedReverb> cs_new_reverb(float sample_rate, uint32_t max_block, uint64_t seed) {
+    return std::unique_ptr<CloudSeedReverb>(new CloudSeedReverb(sample_rate, max_block, seed));
+}
+
+inline std::unique_ptr<CloudSeedReverbF64> cs_new_reverb_f64(float sample_rate, uint32_t max_block, uint64_t seed) {
+    return std::unique_ptr<CloudSeedReverbF64>(new CloudSeedReverbF64(sample_rate, max_block, seed));
 }
 
 /** Formats a normalized parameter value to text. */
@@ -0,0 +1,199 @@
+use crate::Sample;
+use crate::bridge::*;
+use std::ops::{Add, Mul, Sub};
+use std::pin::Pin;
+
+/// A sample type the reverb can process internally: `f32` or `f64`.
+/// See [ReverbController](crate::ReverbController) and
+/// [ReverbControllerF64](crate::ReverbControllerF64).
+///
+/// This trait is sealed and can't be implemented outside of this crate.
+pub trait EngineSample:
+    Sample
+    + Default
+    + PartialEq
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + sealed::Sealed
+{
+}
+
+impl EngineSample for f32 {}
+impl EngineSample for f64 {}
+
+pub(crate) mod sealed {
+    use super::*;
+
+    /// Connects a sample type to the C++ engine instantiated for it.
+    pub trait Sealed: Sized {
+        type Engine: Engine<Self>;
+    }
+
+    impl Sealed for f32 {
+        type Engine = CloudSeedReverb;
+    }
+
+    impl Sealed for f64 {
+        type Engine = CloudSeedReverbF64;
+    }
+
+    /// The methods of the bridged C++ engine, see [CloudSeedReverb].
+    pub trait Engine<S>: cxx::memory::UniquePtrTarget + Sized {
+        fn create(
+            sample_rate: f32,
+            max_block: u32,
+            seed: u64,
+        ) -> Result<cxx::UniquePtr<Self>, cxx::Exception>;
+
+        fn reset(self: Pin<&mut Self>);
+        fn set_sample_rate(self: Pin<&mut Self>, sample_rate: f32) -> Result<(), cxx::Exception>;
+        fn get_sample_rate(&self) -> f32;
+        fn set_parameter(self: Pin<&mut Self>, param_id: u32, value: f32);
+        fn get_parameter(&self, param_id: u32) -> f32;
+        fn get_all_parameters(&self, out: &mut [f32]);
+        fn load_program(self: Pin<&mut Self>, params: &[f32]);
+        fn set_freeze(self: Pin<&mut Self>, enabled: bool);
+        fn set_wet_gain(self: Pin<&mut Self>, gain: f32);
+        fn set_mono_dry(self: Pin<&mut Self>, enabled: bool);
+        fn memory_usage(&self) -> usize;
+        fn propagation_samples(&self) -> u32;
+        fn state_size(&self) -> usize;
+        fn save_state(&self, out: &mut [u8]);
+        fn load_state(self: Pin<&mut Self>, params: &[f32], state: &[u8]) -> bool;
+        fn process(
+            self: Pin<&mut Self>,
+            in_l: &[S],
+            in_r: &[S],
+            out_l: &mut [S],
+            out_r: &mut [S],
+            frames: u32,
+        );
+        #[allow(clippy::too_many_arguments)]
+        fn process_buses(
+            self: Pin<&mut Self>,
+            in_l: &[S],
+            in_r: &[S],
+            out_l: &mut [S],
+            out_r: &mut [S],
+            dry_l: &mut [S],
+            dry_r: &mut [S],
+            early_l: &mut [S],
+            early_r: &mut [S],
+            late_l: &mut [S],
+            late_r: &mut [S],
+            frames: u32,
+        );
+    }
+
+    macro_rules! impl_engine {
+        ($engine:ty, $sample:ty, $new:path) => {
+            impl Engine<$sample> for $engine {
+                fn create(
+                    sample_rate: f32,
+                    max_block: u32,
+                    seed: u64,
+                ) -> Result<cxx::UniquePtr<Self>, cxx::Exception> {
+                    $new(sample_rate, max_block, seed)
+                }
+
+                fn reset(self: Pin<&mut Self>) {
+                    <$engine>::reset(self)
+                }
+
+                fn set_sample_rate(
+                    self: Pin<&mut Self>,
+                    sample_rate: f32,
+                ) -> Result<(), cxx::Exception> {
+                    <$engine>::set_sample_rate(self, sample_rate)
+                }
+
+                fn get_sample_rate(&self) -> f32 {
+                    <$engine>::get_sample_rate(self)
+                }
+
+                fn set_parameter(self: Pin<&mut Self>, param_id: u32, value: f32) {
+                    <$engine>::set_parameter(self, param_id, value)
+                }
+
+                fn get_parameter(&self, param_id: u32) -> f32 {
+                    <$engine>::get_parameter(self, param_id)
+                }
+
+                fn get_all_parameters(&self, out: &mut [f32]) {
+                    <$engine>::get_all_parameters(self, out)
+                }
+
+                fn load_program(self: Pin<&mut Self>, params: &[f32]) {
+                    <$engine>::load_program(self, params)
+                }
+
+                fn set_freeze(self: Pin<&mut Self>, enabled: bool) {
+                    <$engine>::set_freeze(self, enabled)
+                }
+
+                fn set_wet_gain(self: Pin<&mut Self>, gain: f32) {
+                    <$engine>::set_wet_gain(self, gain)
+                }
+
+                fn set_mono_dry(self: Pin<&mut Self>, enabled: bool) {
+                    <$engine>::set_mono_dry(self, enabled)
+                }
+
+                fn memory_usage(&self) -> usize {
+                    <$engine>::memory_usage(self)
+                }
+
+                fn propagation_samples(&self) -> u32 {
+                    <$engine>::propagation_samples(self)
+                }
+
+                fn state_size(&self) -> usize {
+                    <$engine>::state_size(self)
+                }
+
+                fn save_state(&self, out: &mut [u8]) {
+                    <$engine>::save_state(self, out)
+                }
+
+                fn load_state(self: Pin<&mut Self>, params: &[f32], state: &[u8]) -> bool {
+                    <$engine>::load_state(self, params, state)
+                }
+
+                fn process(
+                    self: Pin<&mut Self>,
+                    in_l: &[$sample],
+                    in_r: &[$sample],
+                    out_l: &mut [$sample],
+                    out_r: &mut [$sample],
+                    frames: u32,
+                ) {
+                    <$engine>::process(self, in_l, in_r, out_l, out_r, frames)
+                }
+
+                fn process_buses(
+                    self: Pin<&mut Self>,
+                    in_l: &[$sample],
+                    in_r: &[$sample],
+                    out_l: &mut [$sample],
+                    out_r: &mut [$sample],
+                    dry_l: &mut [$sample],
+                    dry_r: &mut [$sample],
+                    early_l: &mut [$sample],
+                    early_r: &mut [$sample],
+                    late_l: &mut [$sample],
+                    late_r: &mut [$sample],
+                    frames: u32,
+                ) {
+                    <$engine>::process_buses(
+                        self, in_l, in_r, out_l, out_r, dry_l, dry_r, early_l, early_r, late_l,
+                        late_r, frames,
+                    )
+                }
+            }
+        };
+    }
+
+    impl_engine!(CloudSeedReverb, f32, cs_new_reverb);
+    impl_engine!(CloudSeedReverbF64, f64, cs_new_reverb_f64);
+}
@@ -0,0 +1,63 @@
+use std::fmt;
+
+/// Errors reported by the fallible [ReverbController](crate::ReverbController) methods.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    /// The sample rate is zero, negative or not a finite number.
+    InvalidSampleRate(f32),
+    /// The sample rate is outside the supported range
+    /// [MIN_SAMPLE_RATE](crate::MIN_SAMPLE_RATE)..=[MAX_SAMPLE_RATE](crate::MAX_SAMPLE_RATE),
+    /// or not a whole number.
+    UnsupportedSampleRate(f32),
+    /// The maximum block size is zero or larger than
+    /// the internal block size CloudSeedCore was compiled with.
+    InvalidBlockSize { block_size: u32, max: u32 },
+    /// More samples were requested to be processed than the instance's maximum block size.
+    BlockTooLarge {
+        num_samples: u32,
+        max_block_size: u32,
+    },
+    /// A buffer passed to `process` is shorter than the number of samples to process.
+    BufferTooShort { len: usize, num_samples: u32 },
+    /// A [ReverbSnapshot](crate::ReverbSnapshot) is corrupted, or was created
+    /// by a different version of this crate or on a different platform.
+    InvalidSnapshot,
+    /// The underlying C++ code threw an exception, e.g. because memory could not be allocated.
+    Cxx(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidSampleRate(rate) => write!(f, "invalid sample rate {rate}"),
+            Error::UnsupportedSampleRate(rate) => write!(f, "unsupported sample rate {rate}"),
+            Error::InvalidBlockSize { block_size, max } => {
+                write!(
+                    f,
+                    "invalid block size {block_size}, must be in range 1..={max}"
+                )
+            }
+            Error::BlockTooLarge {
+                num_samples,
+                max_block_size,
+            } => write!(
+                f,
+                "cannot process {num_samples} samples, maximum block size is {max_block_size}"
+            ),
+            Error::BufferTooShort { len, num_samples } => write!(
+                f,
+                "buffer of length {len} is too short to process {num_samples} samples"
+            ),
+            Error::InvalidSnapshot => write!(f, "invalid or incompatible reverb snapshot"),
+            Error::Cxx(what) => write!(f, "CloudSeedCore error: {what}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<cxx::Exception> for Error {
+    fn from(e: cxx::Exception) -> Self {
+        Error::Cxx(e.what().to_string())
+    }
+}
@@ -0,0 +1,28 @@
+use crate::{ParamId, Program};
+
+/// A change to be applied at a specific sample offset within a processing block.
+/// See [ReverbController::process_with_events](crate::ReverbController::process_with_events).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ReverbEvent {
+    /// Applies a normalized parameter value in range 0..1.
+    Parameter {
+        sample_offset: u32,
+        id: ParamId,
+        value: f32,
+    },
+    /// Loads a program, ending any ongoing reverb tail.
+    Program {
+        sample_offset: u32,
+        program: Program,
+    },
+}
+
+impl ReverbEvent {
+    /// Returns the offset of this event from the start of the block, in samples.
+    pub fn sample_offset(&self) -> u32 {
+        match self {
+            ReverbEvent::Parameter { sample_offset, .. } => *sample_offset,
+            ReverbEvent::Program { sample_offset, .. } => *sample_offset,
+        }
+    }
+}
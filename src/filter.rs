@@ -0,0 +1,87 @@
+use std::f64::consts::PI;
+
+/// A second order filter section in direct form I,
+/// with constructors after the Audio EQ Cookbook.
+#[derive(Clone, Copy)]
+pub(crate) struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+    x: [f64; 2],
+    y: [f64; 2],
+}
+
+impl Biquad {
+    pub(crate) fn highpass(freq: f64, q: f64, sample_rate: f64) -> Self {
+        let (cos, alpha) = Self::cos_alpha(freq, q, sample_rate);
+        Self::normalized(
+            [(1.0 + cos) / 2.0, -(1.0 + cos), (1.0 + cos) / 2.0],
+            cos,
+            alpha,
+        )
+    }
+
+    pub(crate) fn lowpass(freq: f64, q: f64, sample_rate: f64) -> Self {
+        let (cos, alpha) = Self::cos_alpha(freq, q, sample_rate);
+        Self::normalized(
+            [(1.0 - cos) / 2.0, 1.0 - cos, (1.0 - cos) / 2.0],
+            cos,
+            alpha,
+        )
+    }
+
+    fn cos_alpha(freq: f64, q: f64, sample_rate: f64) -> (f64, f64) {
+        let w = 2.0 * PI * freq / sample_rate;
+        (w.cos(), w.sin() / (2.0 * q))
+    }
+
+    fn normalized(b: [f64; 3], cos: f64, alpha: f64) -> Self {
+        let a0 = 1.0 + alpha;
+        Self::new(b.map(|b| b / a0), [-2.0 * cos / a0, (1.0 - alpha) / a0])
+    }
+
+    /// Creates a section from coefficients normalized to `a0 = 1`.
+    pub(crate) fn new(b: [f64; 3], a: [f64; 2]) -> Self {
+        Self {
+            b,
+            a,
+            x: [0.0; 2],
+            y: [0.0; 2],
+        }
+    }
+
+    pub(crate) fn process(&mut self, x: f64) -> f64 {
+        let y = self.b[0] * x + self.b[1] * self.x[0] + self.b[2] * self.x[1]
+            - self.a[0] * self.y[0]
+            - self.a[1] * self.y[1];
+        self.x = [x, self.x[0]];
+        self.y = [y, self.y[0]];
+        y
+    }
+}
+
+/// Returns the two stages of the K-weighting filter of ITU-R BS.1770 for any sample rate:
+/// a high shelf modelling the head, and a highpass.
+pub(crate) fn k_weighting(sample_rate: f64) -> [Biquad; 2] {
+    let (f0, gain_db, q) = (1681.974450955533, 3.999843853973347, 0.7071752369554196);
+    let k = (PI * f0 / sample_rate).tan();
+    let vh = 10f64.powf(gain_db / 20.0);
+    let vb = vh.powf(0.4996667741545416);
+    let a0 = 1.0 + k / q + k * k;
+    let shelf = Biquad::new(
+        [
+            (vh + vb * k / q + k * k) / a0,
+            2.0 * (k * k - vh) / a0,
+            (vh - vb * k / q + k * k) / a0,
+        ],
+        [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+    );
+
+    let (f0, q) = (38.13547087602444, 0.5003270373238773);
+    let k = (PI * f0 / sample_rate).tan();
+    let a0 = 1.0 + k / q + k * k;
+    let highpass = Biquad::new(
+        [1.0, -2.0, 1.0],
+        [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+    );
+    [shelf, highpass]
+}
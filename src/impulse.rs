@@ -0,0 +1,122 @@
+use crate::reverb::assert_sample_rate;
+use crate::{Program, ReverbController};
+
+/// Seed the modulation of [render_impulse_response] is initialized with,
+/// so that rendering the same program twice gives identical results.
+const IMPULSE_RESPONSE_SEED: u64 = 0;
+
+/// Block size [render_impulse_response] processes in.
+const RENDER_BLOCK_SIZE: u32 = 256;
+
+/// Two channels of audio with the same length, e.g. an impulse response.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StereoBuffer {
+    pub sample_rate: f32,
+    pub left: Vec<f32>,
+    pub right: Vec<f32>,
+}
+
+impl StereoBuffer {
+    /// Creates a buffer of `len` frames of silence.
+    pub fn new(sample_rate: f32, len: usize) -> Self {
+        Self {
+            sample_rate,
+            left: vec![0.0; len],
+            right: vec![0.0; len],
+        }
+    }
+
+    /// Returns the number of frames.
+    pub fn len(&self) -> usize {
+        self.left.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.left.is_empty()
+    }
+
+    /// Returns the length in seconds.
+    pub fn duration(&self) -> f32 {
+        self.len() as f32 / self.sample_rate
+    }
+
+    /// Cuts off the end of the buffer once the energy remaining after that point,
+    /// summed over both channels, is less than `threshold_db` below the total energy.
+    /// For example, a threshold of -60 keeps everything up to the point
+    /// where 99.9999% of the energy has passed.
+    /// A buffer without any energy is cleared entirely.
+    pub fn trim_tail(&mut self, threshold_db: f32) {
+        let energy = |i: usize| {
+            let (l, r) = (self.left[i] as f64, self.right[i] as f64);
+            l * l + r * r
+        };
+        let total: f64 = (0..self.len()).map(energy).sum();
+        let limit = total * 10f64.powf(threshold_db as f64 / 10.0);
+
+        // walk backwards from the end until the remaining energy exceeds the limit
+        let mut remaining = 0.0;
+        let mut len = self.len();
+        while len > 0 && remaining + energy(len - 1) <= limit {
+            remaining += energy(len - 1);
+            len -= 1;
+        }
+        self.left.truncate(len);
+        self.right.truncate(len);
+    }
+
+    /// Returns the buffer with its tail trimmed like [trim_tail](Self::trim_tail).
+    pub fn trimmed(mut self, threshold_db: f32) -> Self {
+        self.trim_tail(threshold_db);
+        self
+    }
+
+    /// Writes the buffer to a stereo 32-bit float WAV file.
+    #[cfg(feature = "wav")]
+    pub fn write_wav(&self, path: impl AsRef<std::path::Path>) -> Result<(), hound::Error> {
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: self.sample_rate.round() as u32,
+            bits_per_sample: 32,
+            sample_format: hound::SampleFormat::Float,
+        };
+        let mut writer = hound::WavWriter::create(path, spec)?;
+        for (&l, &r) in self.left.iter().zip(&self.right) {
+            writer.write_sample(l)?;
+            writer.write_sample(r)?;
+        }
+        writer.finalize()
+    }
+}
+
+/// Renders `max_len` frames of the response of `program` to a unit impulse
+/// fed into both inputs, including the dry signal if the program's `DryOut` is enabled.
+///
+/// The modulation is seeded with a fixed seed, so the result only depends on the arguments.
+/// Use [StereoBuffer::trimmed] to cut off the inaudible end of the tail:
+///
+/// ```
+/// use cloudseedcore_rs::{render_impulse_response, DARK_PLATE};
+///
+/// let ir = render_impulse_response(&DARK_PLATE, 48_000.0, 48_000 * 10).trimmed(-60.0);
+/// assert!(ir.duration() < 10.0);
+/// ```
+///
+/// Panics if the sample rate is not supported, like [ReverbController::new].
+pub fn render_impulse_response(
+    program: &Program,
+    sample_rate: f32,
+    max_len: usize,
+) -> StereoBuffer {
+    assert_sample_rate(sample_rate);
+    let mut reverb =
+        ReverbController::with_seed(sample_rate, RENDER_BLOCK_SIZE, IMPULSE_RESPONSE_SEED);
+    reverb.set_program(program);
+
+    let mut input = vec![0.0f32; max_len];
+    if let Some(first) = input.first_mut() {
+        *first = 1.0;
+    }
+    let mut ir = StereoBuffer::new(sample_rate, max_len);
+    reverb.process_any_len(&input, &input, &mut ir.left, &mut ir.right);
+    ir
+}
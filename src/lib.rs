@@ -1,9 +1,35 @@
 //! This crate provides a safe Rust wrapper around
 //! the [CloudSeedCore](https://github.com/GhostNoteAudio/CloudSeedCore/) Reverb algorithm.
 
+mod analysis;
 mod bridge;
+mod buffer;
+mod buses;
+mod engine;
+mod error;
+mod event;
+mod filter;
+mod impulse;
+mod meter;
+mod mono;
 mod params;
 mod reverb;
+mod shared;
+mod silence;
+mod smoothing;
+mod snapshot;
 
+pub use crate::analysis::*;
+pub use crate::buffer::*;
+pub use crate::buses::*;
+pub use crate::engine::EngineSample;
+pub use crate::error::*;
+pub use crate::event::*;
+pub use crate::impulse::*;
+pub use crate::meter::{MeterPoint, MeterReading, ReverbMeters};
+pub use crate::mono::MonoInput;
 pub use crate::params::*;
 pub use crate::reverb::*;
+pub use crate::shared::ReverbParams;
+pub use crate::smoothing::SmoothingMode;
+pub use crate::snapshot::ReverbSnapshot;
@@ -0,0 +1,363 @@
+use crate::filter::{Biquad, k_weighting};
+use crate::{EngineSample, ReverbBuses, Sample};
+use std::f64::consts::PI;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// A point in the signal path measured by [ReverbMeters].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MeterPoint {
+    /// The input passed to `process`.
+    Input = 0,
+    /// The early reflections, before `EarlyOut` is applied.
+    /// See [ReverbBuses](crate::ReverbBuses).
+    Early = 1,
+    /// The late reverb tail, before `LateOut` is applied.
+    Late = 2,
+    /// The output written by `process`.
+    Output = 3,
+}
+
+impl MeterPoint {
+    pub const ALL: [MeterPoint; 4] = [
+        MeterPoint::Input,
+        MeterPoint::Early,
+        MeterPoint::Late,
+        MeterPoint::Output,
+    ];
+}
+
+/// Levels of the stereo signal at a [MeterPoint].
+/// Levels are in dBFS, with silence at negative infinity.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MeterReading {
+    /// Highest sample magnitude of each channel since the previous reading.
+    pub peak: [f32; 2],
+    /// Highest magnitude of each channel since the previous reading, including peaks
+    /// between samples, estimated with 4x oversampling as in ITU-R BS.1770.
+    pub true_peak: [f32; 2],
+    /// RMS level of each channel, averaged with a time constant of 300 ms.
+    pub rms: [f32; 2],
+    /// K-weighted loudness of the last 3 seconds in LUFS, as in EBU R 128.
+    pub short_term_lufs: f32,
+    /// Correlation between the channels, averaged with a time constant of 300 ms:
+    /// 1 for identical, 0 for unrelated and -1 for inverted channels, or 0 for silence.
+    pub correlation: f32,
+}
+
+/// A lock-free handle to the level meters of a [ReverbController](crate::ReverbController),
+/// obtained through [ReverbController::meters](crate::ReverbController::meters).
+///
+/// The handle can be cloned and shared with other threads, e.g. a UI thread.
+/// While [metering](crate::ReverbController::set_metering) is enabled,
+/// the controller updates the meters at the end of each `process` call.
+/// None of the handle's methods allocate, lock or block.
+#[derive(Clone)]
+pub struct ReverbMeters {
+    shared: Arc<[SharedMeter; 4]>,
+}
+
+/// Levels published by the audio thread, stored as `f32` bits.
+/// Linear levels are positive, so their bits order like their values.
+struct SharedMeter {
+    peak: [AtomicU32; 2],
+    true_peak: [AtomicU32; 2],
+    rms: [AtomicU32; 2],
+    short_term_lufs: AtomicU32,
+    correlation: AtomicU32,
+}
+
+impl ReverbMeters {
+    pub(crate) fn new() -> Self {
+        let zero = || AtomicU32::new(0.0f32.to_bits());
+        Self {
+            shared: Arc::new(std::array::from_fn(|_| SharedMeter {
+                peak: [zero(), zero()],
+                true_peak: [zero(), zero()],
+                rms: [zero(), zero()],
+                short_term_lufs: AtomicU32::new(f32::NEG_INFINITY.to_bits()),
+                correlation: zero(),
+            })),
+        }
+    }
+
+    /// Returns the current levels at the given point, and starts a new peak measurement.
+    /// As reading resets the peaks, each meter point should be read by a single thread.
+    pub fn read(&self, point: MeterPoint) -> MeterReading {
+        let meter = &self.shared[point as usize];
+        let load = |level: &AtomicU32| f32::from_bits(level.load(Ordering::Relaxed));
+        let take = |level: &AtomicU32| to_db(f32::from_bits(level.swap(0, Ordering::Relaxed)));
+        MeterReading {
+            peak: meter.peak.each_ref().map(take),
+            true_peak: meter.true_peak.each_ref().map(take),
+            rms: meter.rms.each_ref().map(|level| to_db(load(level))),
+            short_term_lufs: load(&meter.short_term_lufs),
+            correlation: load(&meter.correlation),
+        }
+    }
+}
+
+fn to_db(level: f32) -> f32 {
+    20.0 * level.log10()
+}
+
+/// Time constant of the RMS and correlation averages in seconds.
+const AVERAGE_TIME: f64 = 0.3;
+/// Length of the short-term loudness window in blocks of 100 ms.
+const LOUDNESS_BLOCKS: usize = 30;
+/// Oversampling factor of the true-peak estimation.
+const OVERSAMPLING: usize = 4;
+/// Length of each polyphase component of the true-peak interpolation filter.
+const TAPS_PER_PHASE: usize = 12;
+
+/// Measures the levels at each [MeterPoint] on the audio thread and publishes them.
+/// Holds buffers for the early and late signals for when `process` isn't given any buses.
+pub(crate) struct Metering<S> {
+    meters: [PointMeter; 4],
+    /// Polyphase components of the windowed-sinc true-peak interpolation filter.
+    interpolation: [[f64; TAPS_PER_PHASE]; OVERSAMPLING],
+    /// Smoothing coefficient of the RMS and correlation averages.
+    average: f64,
+    buses: [Vec<S>; 6],
+}
+
+#[derive(Clone, Copy)]
+struct PointMeter {
+    channels: [ChannelMeter; 2],
+    /// Average product of the channels.
+    product: f64,
+    loudness: LoudnessWindow,
+}
+
+#[derive(Clone, Copy)]
+struct ChannelMeter {
+    k_weighting: [Biquad; 2],
+    /// The most recent samples, newest first.
+    history: [f64; TAPS_PER_PHASE],
+    mean_square: f64,
+}
+
+/// Sums of the K-weighted energy in blocks of 100 ms, covering the last 3 seconds.
+#[derive(Clone, Copy)]
+struct LoudnessWindow {
+    blocks: [f64; LOUDNESS_BLOCKS],
+    /// Index of the block that is overwritten next.
+    next: usize,
+    filled: usize,
+    block_len: u32,
+    current: f64,
+    current_len: u32,
+}
+
+impl<S: EngineSample> Metering<S> {
+    pub(crate) fn new(sample_rate: f32, max_block_size: u32) -> Self {
+        Self {
+            meters: [PointMeter::new(sample_rate); 4],
+            interpolation: interpolation_filter(),
+            average: 1.0 - (-1.0 / (AVERAGE_TIME * sample_rate as f64)).exp(),
+            buses: std::array::from_fn(|_| vec![S::default(); max_block_size as usize]),
+        }
+    }
+
+    /// Updates the filters for the given sample rate and clears the measurements.
+    pub(crate) fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.meters = [PointMeter::new(sample_rate); 4];
+        self.average = 1.0 - (-1.0 / (AVERAGE_TIME * sample_rate as f64)).exp();
+    }
+
+    pub(crate) fn memory_usage(&self) -> usize {
+        self.buses
+            .iter()
+            .map(|b| b.capacity() * size_of::<S>())
+            .sum()
+    }
+
+    /// Returns the internal buffers for capturing the early and late signals,
+    /// along with the state that [MeteringState::measure] needs.
+    pub(crate) fn split(&mut self) -> (MeteringState<'_>, ReverbBuses<'_, S>) {
+        let [dry_l, dry_r, early_l, early_r, late_l, late_r] = &mut self.buses;
+        let state = MeteringState {
+            meters: &mut self.meters,
+            interpolation: &self.interpolation,
+            average: self.average,
+        };
+        let buses = ReverbBuses {
+            dry_l,
+            dry_r,
+            early_l,
+            early_r,
+            late_l,
+            late_r,
+        };
+        (state, buses)
+    }
+}
+
+/// Mutable view of the measurement state of [Metering], separate from its buffers.
+pub(crate) struct MeteringState<'a> {
+    meters: &'a mut [PointMeter; 4],
+    interpolation: &'a [[f64; TAPS_PER_PHASE]; OVERSAMPLING],
+    average: f64,
+}
+
+impl MeteringState<'_> {
+    /// Measures a block of the stereo signal at each point and publishes the levels,
+    /// in the order of [MeterPoint::ALL].
+    pub(crate) fn measure<S: Sample>(&mut self, signals: [[&[S]; 2]; 4], meters: &ReverbMeters) {
+        for ((meter, [l, r]), shared) in self.meters.iter_mut().zip(signals).zip(&*meters.shared) {
+            meter.measure(l, r, self.interpolation, self.average, shared);
+        }
+    }
+}
+
+impl PointMeter {
+    fn new(sample_rate: f32) -> Self {
+        Self {
+            channels: [ChannelMeter::new(sample_rate); 2],
+            product: 0.0,
+            loudness: LoudnessWindow::new(sample_rate),
+        }
+    }
+
+    fn measure<S: Sample>(
+        &mut self,
+        l: &[S],
+        r: &[S],
+        interpolation: &[[f64; TAPS_PER_PHASE]; OVERSAMPLING],
+        average: f64,
+        shared: &SharedMeter,
+    ) {
+        let mut peak = [0.0f64; 2];
+        let mut true_peak = [0.0f64; 2];
+        for (&l, &r) in l.iter().zip(r) {
+            let frame = [l.to_f64(), r.to_f64()];
+            let mut energy = 0.0;
+            for (ch, (channel, &x)) in self.channels.iter_mut().zip(&frame).enumerate() {
+                peak[ch] = peak[ch].max(x.abs());
+                true_peak[ch] = true_peak[ch].max(channel.interpolated_peak(x, interpolation));
+                channel.mean_square += (x * x - channel.mean_square) * average;
+                let weighted = channel.k_weighting.iter_mut().fold(x, |x, f| f.process(x));
+                energy += weighted * weighted;
+            }
+            self.product += (frame[0] * frame[1] - self.product) * average;
+            self.loudness.push(energy);
+        }
+
+        let [left, right] = self.channels.map(|c| c.mean_square);
+        let correlation = if left * right > 1e-20 {
+            (self.product / (left * right).sqrt()).clamp(-1.0, 1.0)
+        } else {
+            0.0
+        };
+        for ch in 0..2 {
+            let bits = |level: f64| (level as f32).to_bits();
+            shared.peak[ch].fetch_max(bits(peak[ch]), Ordering::Relaxed);
+            shared.true_peak[ch].fetch_max(bits(true_peak[ch]), Ordering::Relaxed);
+            let rms = self.channels[ch].mean_square.sqrt();
+            shared.rms[ch].store(bits(rms), Ordering::Relaxed);
+        }
+        let lufs = self.loudness.lufs() as f32;
+        shared
+            .short_term_lufs
+            .store(lufs.to_bits(), Ordering::Relaxed);
+        let correlation = correlation as f32;
+        shared
+            .correlation
+            .store(correlation.to_bits(), Ordering::Relaxed);
+    }
+}
+
+impl ChannelMeter {
+    fn new(sample_rate: f32) -> Self {
+        Self {
+            k_weighting: k_weighting(sample_rate as f64),
+            history: [0.0; TAPS_PER_PHASE],
+            mean_square: 0.0,
+        }
+    }
+
+    /// Adds a sample to the history and returns the highest magnitude
+    /// of the oversampled signal up to it.
+    fn interpolated_peak(
+        &mut self,
+        x: f64,
+        interpolation: &[[f64; TAPS_PER_PHASE]; OVERSAMPLING],
+    ) -> f64 {
+        self.history.copy_within(..TAPS_PER_PHASE - 1, 1);
+        self.history[0] = x;
+        interpolation
+            .iter()
+            .map(|phase| {
+                let y: f64 = phase.iter().zip(&self.history).map(|(h, x)| h * x).sum();
+                y.abs()
+            })
+            .fold(0.0, f64::max)
+    }
+}
+
+impl LoudnessWindow {
+    fn new(sample_rate: f32) -> Self {
+        Self {
+            blocks: [0.0; LOUDNESS_BLOCKS],
+            next: 0,
+            filled: 0,
+            block_len: ((sample_rate / 10.0).round() as u32).max(1),
+            current: 0.0,
+            current_len: 0,
+        }
+    }
+
+    fn push(&mut self, energy: f64) {
+        self.current += energy;
+        self.current_len += 1;
+        if self.current_len == self.block_len {
+            self.blocks[self.next] = self.current;
+            self.next = (self.next + 1) % LOUDNESS_BLOCKS;
+            self.filled = (self.filled + 1).min(LOUDNESS_BLOCKS);
+            self.current = 0.0;
+            self.current_len = 0;
+        }
+    }
+
+    /// Returns the loudness of the window, including the block in progress
+    /// until the window is full.
+    fn lufs(&self) -> f64 {
+        let mut energy: f64 = self.blocks.iter().sum();
+        let mut len = self.filled as f64 * self.block_len as f64;
+        if self.filled < LOUDNESS_BLOCKS {
+            energy += self.current;
+            len += self.current_len as f64;
+        }
+        if len == 0.0 {
+            return f64::NEG_INFINITY;
+        }
+        -0.691 + 10.0 * (energy / len).log10()
+    }
+}
+
+/// Designs a lowpass at the original Nyquist frequency for interpolating by [OVERSAMPLING],
+/// a Blackman-windowed sinc split into its polyphase components,
+/// each normalized to unity gain.
+/// The first component passes the original samples through, delayed by half its length.
+fn interpolation_filter() -> [[f64; TAPS_PER_PHASE]; OVERSAMPLING] {
+    let half = (TAPS_PER_PHASE * OVERSAMPLING / 2) as f64;
+    let tap = |n: usize| {
+        let x = (n as f64 - half) / half;
+        let t = (n as f64 - half) / OVERSAMPLING as f64;
+        let sinc = if t == 0.0 {
+            1.0
+        } else {
+            (PI * t).sin() / (PI * t)
+        };
+        sinc * (0.42 + 0.5 * (PI * x).cos() + 0.08 * (2.0 * PI * x).cos())
+    };
+    std::array::from_fn(|phase| {
+        let mut taps: [f64; TAPS_PER_PHASE] =
+            std::array::from_fn(|k| tap(k * OVERSAMPLING + phase));
+        let sum: f64 = taps.iter().sum();
+        for t in &mut taps {
+            *t /= sum;
+        }
+        taps
+    })
+}
@@ -0,0 +1,119 @@
+use crate::snapshot::{SnapshotReader, SnapshotWriter};
+use crate::{EngineSample, Error, MAX_SAMPLE_RATE};
+
+/// How a mono signal is fed into the stereo reverb.
+/// See [ReverbController::set_mono_input](crate::ReverbController::set_mono_input).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MonoInput {
+    /// Both reverb channels receive the same signal.
+    /// The channels use different delay patterns, so the tail is still stereo.
+    #[default]
+    Duplicate,
+    /// The right channel of the reverb receives an allpass-filtered copy of the signal,
+    /// which has the same spectrum but a scrambled phase.
+    /// This also widens the early reflections.
+    /// The dry signal is left unfiltered on both channels, so it stays mono-compatible.
+    Decorrelated,
+}
+
+/// Delays of the allpass stages in milliseconds, chosen to be mutually prime at common rates.
+const DELAYS_MS: [f32; 3] = [1.7, 3.1, 4.3];
+const FEEDBACK: f32 = 0.6;
+
+/// Cascade of allpass filters decorrelating a copy of a mono input.
+#[derive(Clone)]
+pub(crate) struct Decorrelator<S> {
+    stages: [AllpassStage<S>; 3],
+}
+
+#[derive(Clone)]
+struct AllpassStage<S> {
+    /// Sized for [MAX_SAMPLE_RATE], of which the first `delay` samples are used.
+    buffer: Vec<S>,
+    delay: usize,
+    pos: usize,
+}
+
+impl<S: EngineSample> Decorrelator<S> {
+    pub(crate) fn new(sample_rate: f32) -> Self {
+        let mut decorrelator = Self {
+            stages: DELAYS_MS.map(|ms| AllpassStage {
+                buffer: vec![S::default(); delay_samples(ms, MAX_SAMPLE_RATE)],
+                delay: 1,
+                pos: 0,
+            }),
+        };
+        decorrelator.set_sample_rate(sample_rate);
+        decorrelator
+    }
+
+    /// Updates the delays for the given sample rate and clears the filters.
+    pub(crate) fn set_sample_rate(&mut self, sample_rate: f32) {
+        for (stage, ms) in self.stages.iter_mut().zip(DELAYS_MS) {
+            stage.delay = delay_samples(ms, sample_rate.min(MAX_SAMPLE_RATE));
+        }
+        self.reset();
+    }
+
+    pub(crate) fn reset(&mut self) {
+        for stage in &mut self.stages {
+            stage.buffer.fill(S::default());
+            stage.pos = 0;
+        }
+    }
+
+    pub(crate) fn memory_usage(&self) -> usize {
+        self.stages
+            .iter()
+            .map(|s| s.buffer.capacity() * size_of::<S>())
+            .sum()
+    }
+
+    pub(crate) fn process(&mut self, input: &[S], output: &mut [S]) {
+        let feedback = S::from_f32(FEEDBACK);
+        output.copy_from_slice(input);
+        for stage in &mut self.stages {
+            for x in output.iter_mut() {
+                let delayed = stage.buffer[stage.pos];
+                let v = *x + feedback * delayed;
+                *x = delayed - feedback * v;
+                stage.buffer[stage.pos] = v;
+                stage.pos = if stage.pos + 1 == stage.delay {
+                    0
+                } else {
+                    stage.pos + 1
+                };
+            }
+        }
+    }
+
+    /// Appends the filter state to a snapshot.
+    pub(crate) fn write_state(&self, w: &mut SnapshotWriter) {
+        for stage in &self.stages {
+            w.u32(stage.pos as u32);
+            for &value in &stage.buffer[..stage.delay] {
+                w.sample(value);
+            }
+        }
+    }
+
+    /// Reads the filter state written by [write_state](Self::write_state)
+    /// at the given sample rate.
+    pub(crate) fn read_state(r: &mut SnapshotReader, sample_rate: f32) -> Result<Self, Error> {
+        let mut decorrelator = Self::new(sample_rate);
+        for stage in &mut decorrelator.stages {
+            stage.pos = r.u32()? as usize;
+            if stage.pos >= stage.delay {
+                return Err(Error::InvalidSnapshot);
+            }
+            for value in &mut stage.buffer[..stage.delay] {
+                *value = r.sample()?;
+            }
+        }
+        Ok(decorrelator)
+    }
+}
+
+fn delay_samples(ms: f32, sample_rate: f32) -> usize {
+    ((ms * sample_rate / 1000.0).round() as usize).max(1)
+}
@@ -29,24 +29,24 @@ pub fn parse_parameter_text(param: ParamId, text: &str) -> Option<f32> {
         | ParamId::EarlyDiffuseFeedback
         | ParamId::TapDecay
         | ParamId::LateDiffuseFeedback
-        | ParamId::EqCrossSeed => Some((extract_number(&t)? / 100.0).clamp(0.0, 1.0)),
+        | ParamId::EqCrossSeed => Some((extract_truncated(&t)? / 100.0).clamp(0.0, 1.0)),
 
         // seeds
         ParamId::SeedTap
         | ParamId::SeedDiffusion
         | ParamId::SeedDelay
-        | ParamId::SeedPostDiffusion => Some((extract_number(&t)? / 999.999).clamp(0.0, 1.0)),
+        | ParamId::SeedPostDiffusion => Some((extract_truncated(&t)? / 999.999).clamp(0.0, 1.0)),
 
         // low freq
         ParamId::LowCut => {
-            let hz = extract_number(&t)?;
+            let hz = extract_truncated(&t)?;
             let x = (hz - 20.0) / 980.0;
             Some(inv_resp4oct(x))
         }
 
         // high freq
         ParamId::HighCut | ParamId::EqHighFreq | ParamId::EqCutoff => {
-            let hz = extract_number(&t)?;
+            let hz = extract_truncated(&t)?;
             let x = (hz - 400.0) / 19600.0;
             Some(inv_resp4oct(x))
         }
@@ -61,31 +61,33 @@ pub fn parse_parameter_text(param: ParamId, text: &str) -> Option<f32> {
         }
 
         ParamId::TapCount => {
-            let n = extract_number(&t)?;
+            let n = extract_truncated(&t)?;
             Some(((n - 1.0) / 255.0).clamp(0.0, 1.0))
         }
 
         ParamId::TapPredelay => {
-            let ms = extract_number(&t)?;
+            let ms = extract_truncated(&t)?;
             Some(inv_resp1dec(ms / 500.0))
         }
 
         ParamId::TapLength => {
-            let ms = extract_number(&t)?;
-            Some((ms - 10.0) / 990.0)
+            let ms = extract_truncated(&t)?;
+            Some(((ms - 10.0) / 990.0).clamp(0.0, 1.0))
         }
 
         ParamId::EarlyDiffuseCount => {
-            let n = extract_number(&t)?;
-            Some((n - 1.0) / 11.999)
+            let n = extract_truncated(&t)?;
+            Some(((n - 1.0) / 11.999).clamp(0.0, 1.0))
         }
 
         ParamId::EarlyDiffuseDelay => {
-            let ms = extract_number(&t)?;
-            Some((ms - 10.0) / 90.0)
+            let ms = extract_truncated(&t)?;
+            Some(((ms - 10.0) / 90.0).clamp(0.0, 1.0))
         }
 
-        ParamId::EarlyDiffuseModAmount => Some((extract_number(&t)? / 100.0) / 2.5),
+        ParamId::EarlyDiffuseModAmount => {
+            Some(((extract_truncated(&t)? / 100.0) / 2.5).clamp(0.0, 1.0))
+        }
 
         ParamId::EarlyDiffuseModRate => {
             let hz = extract_number(&t)?;
@@ -95,33 +97,36 @@ pub fn parse_parameter_text(param: ParamId, text: &str) -> Option<f32> {
         ParamId::LateMode => Some(if t.contains("POST") { 1.0 } else { 0.0 }),
 
         ParamId::LateLineCount => {
-            let n = extract_number(&t)?;
-            Some((n - 1.0) / 11.999)
+            let n = extract_truncated(&t)?;
+            Some(((n - 1.0) / 11.999).clamp(0.0, 1.0))
         }
 
         ParamId::LateDiffuseCount => {
-            let n = extract_number(&t)?;
-            Some((n - 1.0) / 7.999)
+            let n = extract_truncated(&t)?;
+            Some(((n - 1.0) / 7.999).clamp(0.0, 1.0))
         }
 
         ParamId::LateLineSize => {
-            let ms = extract_number(&t)?;
+            let ms = extract_truncated(&t)?;
             let x = (ms - 20.0) / 980.0;
             Some(inv_resp2dec(x))
         }
 
         ParamId::LateLineModAmount | ParamId::LateDiffuseModAmount => {
-            Some((extract_number(&t)? / 100.0) / 2.5)
+            Some(((extract_truncated(&t)? / 100.0) / 2.5).clamp(0.0, 1.0))
         }
 
         ParamId::LateDiffuseDelay => {
-            let ms = extract_number(&t)?;
-            Some((ms - 10.0) / 90.0)
+            let ms = extract_truncated(&t)?;
+            Some(((ms - 10.0) / 90.0).clamp(0.0, 1.0))
         }
 
         ParamId::LateLineDecay => {
-            let n = extract_number(&t)?;
-            let sec = if t.contains("MS") { n / 1000.0 } else { n };
+            let sec = if t.contains("MS") {
+                extract_truncated(&t)? / 1000.0
+            } else {
+                extract_number(&t)?
+            };
             let base = (sec - 0.05) / 59.95;
             Some(inv_resp3dec(base))
         }
@@ -132,7 +137,7 @@ pub fn parse_parameter_text(param: ParamId, text: &str) -> Option<f32> {
         }
 
         ParamId::EqLowFreq => {
-            let hz = extract_number(&t)?;
+            let hz = extract_truncated(&t)?;
             let x = (hz - 20.0) / 980.0;
             Some(inv_resp3oct(x))
         }
@@ -151,11 +156,21 @@ fn extract_number(input: &str) -> Option<f32> {
     let start_index = input.find(|c: char| c.is_numeric() || c == '.' || c == '-')?;
 
     // parse into f32
-    f32::from_lexical_partial((&input[start_index..]).as_bytes())
+    f32::from_lexical_partial(&input.as_bytes()[start_index..])
         .ok()
         .map(|i| i.0)
 }
 
+/// Extracts a number that CloudSeedCore displays truncated to an integer,
+/// nudged slightly upwards so float rounding in the inverse mapping
+/// can't push the value below the displayed integer.
+fn extract_truncated(input: &str) -> Option<f32> {
+    extract_number(input).map(|n| n + TRUNCATION_MARGIN)
+}
+
+/// Fraction of a display unit added to truncated numbers when parsing.
+const TRUNCATION_MARGIN: f32 = 0.01;
+
 // the corresponding inverse functions to the
 // "Resp" functions declared in Cloudseed/DSP/Utils.h
 
@@ -180,6 +195,23 @@ const OCT7_MULT: f32 = (128.0 / 127.0)*0.0078125;
 const OCT8_MULT: f32 = (256.0 / 255.0)*0.00390625;
  */
 
+// the "Resp" functions themselves, for computing scaled parameter values
+
+#[inline]
+pub(crate) fn resp1dec(x: f32) -> f32 {
+    (10f32.powf(x) - 1.0) * DEC1_MULT
+}
+
+#[inline]
+pub(crate) fn resp2dec(x: f32) -> f32 {
+    (10f32.powf(2.0 * x) - 1.0) * DEC2_MULT
+}
+
+#[inline]
+pub(crate) fn resp3dec(x: f32) -> f32 {
+    (10f32.powf(3.0 * x) - 1.0) * DEC3_MULT
+}
+
 #[inline]
 fn safe_positive(v: f32) -> f32 {
     if v.is_finite() && v > 0.0 {
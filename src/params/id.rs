@@ -106,3 +106,28 @@ impl ParamId {
         ParamId::SeedPostDiffusion,
     ];
 }
+
+impl ParamId {
+    /// Returns whether this parameter is continuous,
+    /// meaning it is ramped rather than stepped when parameter smoothing is enabled.
+    /// Parameters that reconfigure delay lines, counts or seeds are always stepped.
+    pub fn is_continuous(self) -> bool {
+        matches!(
+            self,
+            ParamId::InputMix
+                | ParamId::LowCut
+                | ParamId::HighCut
+                | ParamId::DryOut
+                | ParamId::EarlyOut
+                | ParamId::LateOut
+                | ParamId::TapDecay
+                | ParamId::EarlyDiffuseFeedback
+                | ParamId::LateDiffuseFeedback
+                | ParamId::EqLowFreq
+                | ParamId::EqHighFreq
+                | ParamId::EqCutoff
+                | ParamId::EqLowGain
+                | ParamId::EqHighGain
+        )
+    }
+}
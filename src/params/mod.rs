@@ -1,8 +1,10 @@
 mod conversion;
 mod id;
+mod tail;
 
 pub use conversion::*;
 pub use id::*;
+pub use tail::DEFAULT_TAIL_THRESHOLD_DB;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum LateMode {
@@ -415,6 +417,8 @@ const fn bool_to_param(value: bool) -> f32 {
 }
 
 /// "Dark Plate" preset as defined by CloudSeedCore
+// values are copied verbatim from CloudSeedCore's Programs.h
+#[allow(clippy::excessive_precision)]
 pub static DARK_PLATE: Program = Program {
     // Mix
     interpolation: true,
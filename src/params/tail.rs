@@ -0,0 +1,65 @@
+use crate::Program;
+use crate::params::conversion::{resp1dec, resp2dec, resp3dec};
+
+/// Level relative to the input at which [ReverbController::tail_samples](crate::ReverbController::tail_samples)
+/// considers the tail to have ended.
+pub const DEFAULT_TAIL_THRESHOLD_DB: f32 = -90.0;
+
+/// Output levels at or below this are muted, see `ReverbChannel::SetParameter`.
+const MUTED_DB: f32 = -30.0;
+
+/// Tail length estimation, mirroring the scaling in CloudSeedCore's `ScaleParam`
+/// and the feedback math in `ReverbChannel::UpdateLines`.
+impl Program {
+    /// Estimates the number of samples it takes at the given sample rate
+    /// for the output caused by a full-scale input to decay below `threshold_db`
+    /// relative to the input level.
+    ///
+    /// This is an upper bound for the undamped delay lines: the EQ and cut filters,
+    /// which only shorten the tail, are not taken into account.
+    /// Returns 0 if all outputs that could exceed the threshold are muted.
+    pub fn estimated_tail(&self, sample_rate: f32, threshold_db: f32) -> u32 {
+        let ms_to_samples = |ms: f32| (ms / 1000.0 * sample_rate) as f64;
+
+        let predelay = resp1dec(self.tap_predelay) * 500.0;
+        let tap_length = if self.tap_enabled {
+            10.0 + self.tap_length * 990.0
+        } else {
+            0.0
+        };
+        let early = ms_to_samples(predelay + tap_length);
+
+        // a line's delay is scaled by 0.5 to 1.5 from its seed,
+        // and its feedback is set so it decays by 60 dB over the decay time
+        let line_size = 20.0 + resp2dec(self.late_line_size) * 980.0;
+        let line_mod = self.late_line_mod_amount * 2.5;
+        let line = ms_to_samples(1.5 * line_size).max(ms_to_samples(line_mod) + 2.0);
+        let decay = ms_to_samples(self.late_decay_time() * 1000.0);
+        let db_per_iteration = line / decay * 60.0;
+
+        let level_db = |value: f32| {
+            let db = -30.0 + value * 30.0;
+            if db <= MUTED_DB {
+                f32::NEG_INFINITY
+            } else {
+                db
+            }
+        };
+
+        let mut tail: f64 = 0.0;
+        if level_db(self.early_out) > threshold_db {
+            tail = early;
+        }
+        let late_db = level_db(self.late_out);
+        if late_db > threshold_db {
+            let iterations = ((late_db - threshold_db) as f64 / db_per_iteration).ceil();
+            tail = tail.max(early + line * (iterations + 1.0));
+        }
+        tail.ceil().min(u32::MAX as f64) as u32
+    }
+
+    /// Returns the time in seconds the late delay lines take to decay by 60 dB.
+    pub(crate) fn late_decay_time(&self) -> f32 {
+        0.05 + resp3dec(self.late_line_decay) * 59.95
+    }
+}
@@ -1,27 +1,288 @@
-use crate::bridge::*;
-use crate::{ParamId, Program};
+use crate::engine::sealed::Engine;
+use crate::meter::{Metering, MeteringState};
+use crate::mono::Decorrelator;
+use crate::shared::clamp_parameter;
+use crate::silence::SilenceDetector;
+use crate::smoothing::ParamSmoother;
+use crate::snapshot::{SnapshotReader, SnapshotWriter};
+use crate::{
+    DEFAULT_TAIL_THRESHOLD_DB, EngineSample, Error, InputBuffer, Interleaved, MonoInput,
+    OutputBuffer, ParamId, Program, ReverbBuses, ReverbEvent, ReverbMeters, ReverbParams,
+    ReverbSnapshot, SmoothingMode,
+};
+use std::hash::{BuildHasher, RandomState};
+
+/// Lowest sample rate in Hz accepted by [ReverbController::try_new].
+pub const MIN_SAMPLE_RATE: f32 = 8_000.0;
+/// Highest sample rate in Hz accepted by [ReverbController::try_new].
+/// The delay buffers are sized in proportion to the sample rate,
+/// see [memory_usage](ReverbController::memory_usage).
+pub const MAX_SAMPLE_RATE: f32 = 384_000.0;
+
+/// Block size CloudSeedCore processes internally, and the largest `max_block_size`
+/// a [ReverbController] can be created with. Larger blocks are split into chunks of this size.
+/// Defaults to 1024 and can be set at build time
+/// through the `CLOUDSEED_MAX_BUFFER_SIZE` environment variable, in range 16..=8192.
+pub const MAX_INTERNAL_BLOCK_SIZE: u32 = config::MAX_INTERNAL_BLOCK_SIZE;
+
+/// Constants generated by build.rs.
+mod config {
+    include!(concat!(env!("OUT_DIR"), "/config.rs"));
+}
+
+/// Number of samples between parameter updates while a parameter is being smoothed.
+const SMOOTHING_INTERVAL: u32 = 16;
 
 /// A stereo reverb.
 ///
 /// This is a safe wrapper around the CloudSeedCore ReverbController.
-pub struct ReverbController {
-    inner: cxx::UniquePtr<CloudSeedReverb>,
+/// `S` is the sample type the engine processes, `f32` unless created as a [ReverbControllerF64].
+///
+/// # Real-time safety
+///
+/// Once constructed, the processing methods, [set_parameter](Self::set_parameter),
+/// [set_program](Self::set_program), [set_freeze](Self::set_freeze) and
+/// [reset](Self::reset) never allocate or lock, so they may be called on the audio thread.
+/// Construction, [set_sample_rate](Self::set_sample_rate) and the first
+/// [set_program_crossfade](Self::set_program_crossfade) call allocate.
+/// While [auto gain](Self::set_auto_gain) is enabled, loading a program
+/// or changing `LateLineCount` on the controller allocates as well.
+pub struct ReverbController<S: EngineSample = f32> {
+    inner: cxx::UniquePtr<S::Engine>,
     max_block_size: u32,
+    seed: u64,
+
+    smoothing_time: f32,
+    smoothing_mode: SmoothingMode,
+    smoothers: [ParamSmoother; 45],
+
+    crossfade: Crossfade<S>,
+    frozen: bool,
+    silence: SilenceDetector,
+    /// Buffers capturing the signal components for silence detection
+    /// when the caller doesn't request them, allocated once detection is enabled.
+    silence_buses: [Vec<S>; 6],
+    auto_gain: bool,
+    /// Loudness compensation in dB applied to the wet signal of the active engine.
+    auto_gain_db: f32,
+    /// Ramps the linear gain of the compensation when it changes along with `LateLineCount`.
+    wet_gain: ParamSmoother,
+
+    params: ReverbParams,
+    meters: ReverbMeters,
+    metering: Option<Metering<S>>,
+
+    mono_input: MonoInput,
+    decorrelator: Decorrelator<S>,
+
+    /// Planar input and output buffers of `max_block_size` samples
+    /// for converting between sample formats and layouts.
+    scratch: [Vec<S>; 4],
+}
+
+/// State of a crossfade between two programs.
+/// See [ReverbController::set_program_crossfade].
+struct Crossfade<S: EngineSample> {
+    /// Engine running the outgoing program.
+    /// Allocated on the first crossfade and kept as a spare afterwards.
+    outgoing: cxx::UniquePtr<S::Engine>,
+    /// Output of the outgoing engine: main output, followed by the buses.
+    bufs: [Vec<S>; 8],
+    length: u32,
+    position: u32,
+}
+
+impl<S: EngineSample> Crossfade<S> {
+    fn new() -> Self {
+        Self {
+            outgoing: cxx::UniquePtr::null(),
+            bufs: Default::default(),
+            length: 0,
+            position: 0,
+        }
+    }
+
+    fn is_active(&self) -> bool {
+        self.position < self.length
+    }
+
+    fn cancel(&mut self) {
+        self.position = self.length;
+    }
 }
 
-// SAFETY: the underlying CloudSeedCore ReverbController written in C++ is single-threaded.
-unsafe impl Send for ReverbController {}
-unsafe impl Sync for ReverbController {}
+// SAFETY: the underlying CloudSeedCore ReverbController written in C++ has no thread affinity,
+// so it can be moved between threads. It is not Sync: use [ReverbParams] to
+// change parameters from other threads.
+unsafe impl<S: EngineSample> Send for ReverbController<S> {}
 
 impl ReverbController {
     /// Creates a reverb instance with the given sample rate
     /// and maximum block size that will be passed to `process`.
+    /// The modulation is seeded randomly, see [with_seed](Self::with_seed).
+    /// Panics if the sample rate is not a whole number in range
+    /// [MIN_SAMPLE_RATE]..=[MAX_SAMPLE_RATE], `max_block_size` is not in range
+    /// 1..=[MAX_INTERNAL_BLOCK_SIZE] or the underlying C++ reverb can't be allocated.
+    /// Use [try_new](Self::try_new) for validated, fallible construction.
     pub fn new(sample_rate: f32, max_block_size: u32) -> Self {
-        let inner = cs_new_reverb(sample_rate, max_block_size);
+        Self::with_seed(sample_rate, max_block_size, random_seed())
+    }
+
+    /// Creates a reverb instance like [new](Self::new), seeding the phases
+    /// of its modulated delays and allpasses from `seed`.
+    /// Instances created with the same seed produce bit-identical output
+    /// for identical programs, inputs and block sizes.
+    pub fn with_seed(sample_rate: f32, max_block_size: u32, seed: u64) -> Self {
+        Self::create(sample_rate, max_block_size, seed)
+    }
+
+    /// Creates a reverb instance like [new](Self::new), but validates the arguments
+    /// and reports failures instead of panicking.
+    /// The sample rate must be a whole number in range
+    /// [MIN_SAMPLE_RATE]..=[MAX_SAMPLE_RATE], and the maximum block size
+    /// must be in range 1..=[MAX_INTERNAL_BLOCK_SIZE].
+    pub fn try_new(sample_rate: f32, max_block_size: u32) -> Result<Self, Error> {
+        Self::try_with_seed(sample_rate, max_block_size, random_seed())
+    }
+
+    /// Creates a reverb instance like [try_new](Self::try_new),
+    /// seeding its modulation like [with_seed](Self::with_seed).
+    pub fn try_with_seed(sample_rate: f32, max_block_size: u32, seed: u64) -> Result<Self, Error> {
+        Self::try_create(sample_rate, max_block_size, seed)
+    }
+
+    /// Returns the block size CloudSeedCore processes internally,
+    /// see [MAX_INTERNAL_BLOCK_SIZE].
+    pub fn max_internal_block_size() -> u32 {
+        MAX_INTERNAL_BLOCK_SIZE
+    }
+}
+
+/// A stereo reverb processing `f64` samples, see [ReverbController].
+///
+/// The whole signal path runs in double precision, which lowers the noise floor
+/// of long, slowly decaying tails and of offline renders that are processed further.
+/// It takes about twice the memory and is somewhat slower than the `f32` engine.
+/// Parameters are still `f32`.
+pub type ReverbControllerF64 = ReverbController<f64>;
+
+impl ReverbController<f64> {
+    /// Creates a double-precision reverb like [ReverbController::new].
+    pub fn new_f64(sample_rate: f32, max_block_size: u32) -> Self {
+        Self::create(sample_rate, max_block_size, random_seed())
+    }
+
+    /// Creates a double-precision reverb like [ReverbController::with_seed].
+    pub fn with_seed_f64(sample_rate: f32, max_block_size: u32, seed: u64) -> Self {
+        Self::create(sample_rate, max_block_size, seed)
+    }
+
+    /// Creates a double-precision reverb like [ReverbController::try_new].
+    pub fn try_new_f64(sample_rate: f32, max_block_size: u32) -> Result<Self, Error> {
+        Self::try_create(sample_rate, max_block_size, random_seed())
+    }
+
+    /// Creates a double-precision reverb like [ReverbController::try_with_seed].
+    pub fn try_with_seed_f64(
+        sample_rate: f32,
+        max_block_size: u32,
+        seed: u64,
+    ) -> Result<Self, Error> {
+        Self::try_create(sample_rate, max_block_size, seed)
+    }
+}
+
+impl<S: EngineSample> ReverbController<S> {
+    fn create(sample_rate: f32, max_block_size: u32, seed: u64) -> Self {
+        assert_sample_rate(sample_rate);
+        assert!(
+            (1..=MAX_INTERNAL_BLOCK_SIZE).contains(&max_block_size),
+            "max_block_size must be in range 1..={MAX_INTERNAL_BLOCK_SIZE}, got {max_block_size}"
+        );
+        let inner = S::Engine::create(sample_rate, max_block_size, seed)
+            .expect("failed to create CloudSeedCore reverb");
+        Self::from_inner(inner, max_block_size, seed)
+    }
+
+    fn try_create(sample_rate: f32, max_block_size: u32, seed: u64) -> Result<Self, Error> {
+        validate_sample_rate(sample_rate)?;
+
+        let max = MAX_INTERNAL_BLOCK_SIZE;
+        if max_block_size == 0 || max_block_size > max {
+            return Err(Error::InvalidBlockSize {
+                block_size: max_block_size,
+                max,
+            });
+        }
+
+        let inner = S::Engine::create(sample_rate, max_block_size, seed)?;
+        Ok(Self::from_inner(inner, max_block_size, seed))
+    }
+
+    fn from_inner(inner: cxx::UniquePtr<S::Engine>, max_block_size: u32, seed: u64) -> Self {
+        let sample_rate = inner.get_sample_rate();
+        let mut params = [0.0f32; 45];
+        inner.get_all_parameters(&mut params);
         Self {
             inner,
             max_block_size,
+            seed,
+            smoothing_time: 0.0,
+            smoothing_mode: SmoothingMode::default(),
+            // start from the engine's values, so the first ramp doesn't start at 0
+            smoothers: params.map(ParamSmoother::new),
+            crossfade: Crossfade::new(),
+            frozen: false,
+            silence: SilenceDetector::default(),
+            silence_buses: Default::default(),
+            auto_gain: false,
+            auto_gain_db: 0.0,
+            wet_gain: ParamSmoother::new(1.0),
+            params: ReverbParams::new(&Program::from_array(params)),
+            meters: ReverbMeters::new(),
+            metering: None,
+            mono_input: MonoInput::default(),
+            decorrelator: Decorrelator::new(sample_rate),
+            scratch: std::array::from_fn(|_| vec![S::default(); max_block_size as usize]),
+        }
+    }
+
+    /// Returns a handle for changing this reverb's parameters from other threads.
+    /// Changes made through the handle are applied at the start of the next `process` call.
+    pub fn params(&self) -> ReverbParams {
+        self.params.clone()
+    }
+
+    /// Returns a handle for reading this reverb's level meters from other threads.
+    /// The meters are only updated while [metering](Self::set_metering) is enabled.
+    pub fn meters(&self) -> ReverbMeters {
+        self.meters.clone()
+    }
+
+    /// Enables or disables measuring the levels of the input, early, late
+    /// and output signals in each `process` call, which is disabled by default.
+    /// See [ReverbMeters].
+    ///
+    /// Enabling allocates buffers for capturing the early and late signals.
+    pub fn set_metering(&mut self, enabled: bool) {
+        if enabled == self.metering.is_some() {
+            return;
         }
+        self.metering = enabled.then(|| {
+            let sample_rate = self.inner.as_ref().unwrap().get_sample_rate();
+            Metering::new(sample_rate, self.max_block_size)
+        });
+    }
+
+    /// Returns whether metering is enabled.
+    pub fn is_metering(&self) -> bool {
+        self.metering.is_some()
+    }
+
+    /// Returns the seed this instance's modulation was initialized from.
+    /// Passing it to [with_seed](Self::with_seed) reproduces this instance's output.
+    pub fn seed(&self) -> u64 {
+        self.seed
     }
 
     /// Returns the maximum block size that can be passed to `process` for this instance.
@@ -29,15 +290,340 @@ impl ReverbController {
         self.max_block_size
     }
 
+    /// Returns the memory in bytes used by this reverb, including its delay buffers
+    /// and the spare engine kept for crossfades.
+    /// The delay buffers grow in proportion to the sample rate,
+    /// at 48 kHz a single engine uses about 14 MB.
+    pub fn memory_usage(&self) -> usize {
+        let fade = &self.crossfade;
+        let outgoing = fade.outgoing.as_ref().map_or(0, |e| e.memory_usage());
+        let bufs: usize = fade
+            .bufs
+            .iter()
+            .chain(&self.scratch)
+            .chain(&self.silence_buses)
+            .map(|b| b.capacity() * size_of::<S>())
+            .sum();
+        size_of::<Self>()
+            + self.inner.as_ref().unwrap().memory_usage()
+            + outgoing
+            + bufs
+            + self.decorrelator.memory_usage()
+            + self.metering.as_ref().map_or(0, |m| m.memory_usage())
+    }
+
     /// Processes a stereo signal.
     /// All buffers must be at least `num_samples` long.
     /// Panics if `num_samples` is greater than `max_block_size`.
     pub fn process(
         &mut self,
-        in_l: &[f32],
-        in_r: &[f32],
-        out_l: &mut [f32],
-        out_r: &mut [f32],
+        in_l: &[S],
+        in_r: &[S],
+        out_l: &mut [S],
+        out_r: &mut [S],
+        num_samples: u32,
+    ) {
+        self.process_smoothed(in_l, in_r, out_l, out_r, None, num_samples);
+    }
+
+    /// Processes a stereo signal like [process](Self::process),
+    /// but returns an error instead of panicking if `num_samples` is greater than
+    /// `max_block_size` or any buffer is shorter than `num_samples`.
+    pub fn try_process(
+        &mut self,
+        in_l: &[S],
+        in_r: &[S],
+        out_l: &mut [S],
+        out_r: &mut [S],
+        num_samples: u32,
+    ) -> Result<(), Error> {
+        if num_samples > self.max_block_size {
+            return Err(Error::BlockTooLarge {
+                num_samples,
+                max_block_size: self.max_block_size,
+            });
+        }
+
+        let lengths = [in_l.len(), in_r.len(), out_l.len(), out_r.len()];
+        if let Some(&len) = lengths.iter().find(|&&len| len < num_samples as usize) {
+            return Err(Error::BufferTooShort { len, num_samples });
+        }
+
+        self.process(in_l, in_r, out_l, out_r, num_samples);
+        Ok(())
+    }
+
+    /// Processes a stereo signal of any length, such as a whole file,
+    /// splitting it into blocks of up to `max_block_size` samples internally.
+    /// All buffers must have the same length.
+    pub fn process_any_len(&mut self, in_l: &[S], in_r: &[S], out_l: &mut [S], out_r: &mut [S]) {
+        let len = in_l.len();
+        assert!(in_r.len() == len && out_l.len() == len && out_r.len() == len);
+
+        let block = self.max_block_size.max(1) as usize;
+        for start in (0..len).step_by(block) {
+            let end = (start + block).min(len);
+            self.process(
+                &in_l[start..end],
+                &in_r[start..end],
+                &mut out_l[start..end],
+                &mut out_r[start..end],
+                (end - start) as u32,
+            );
+        }
+    }
+
+    /// Processes interleaved stereo frames of any length,
+    /// i.e. left and right samples alternating.
+    /// Both buffers must have the same, even length.
+    pub fn process_interleaved(&mut self, input: &[S], output: &mut [S]) {
+        self.process_buffers(&Interleaved(input), &mut Interleaved(output));
+    }
+
+    /// Processes a stereo signal of any length in place,
+    /// replacing the input with the output.
+    /// Both buffers must have the same length.
+    pub fn process_in_place(&mut self, left: &mut [S], right: &mut [S]) {
+        let len = left.len();
+        assert!(right.len() == len);
+
+        let mut scratch = std::mem::take(&mut self.scratch);
+        let [in_l, in_r, ..] = &mut scratch;
+        let block = self.max_block_size.max(1) as usize;
+        for start in (0..len).step_by(block) {
+            let end = (start + block).min(len);
+            let n = end - start;
+            in_l[..n].copy_from_slice(&left[start..end]);
+            in_r[..n].copy_from_slice(&right[start..end]);
+            self.process(
+                &in_l[..n],
+                &in_r[..n],
+                &mut left[start..end],
+                &mut right[start..end],
+                n as u32,
+            );
+        }
+        self.scratch = scratch;
+    }
+
+    /// Processes a mono signal into stereo output,
+    /// feeding it to both reverb channels as set by [set_mono_input](Self::set_mono_input).
+    /// All buffers must be at least `num_samples` long.
+    /// Panics if `num_samples` is greater than `max_block_size`.
+    pub fn process_mono_to_stereo(
+        &mut self,
+        input: &[S],
+        out_l: &mut [S],
+        out_r: &mut [S],
+        num_samples: u32,
+    ) {
+        assert!(num_samples <= self.max_block_size);
+        let mut scratch = std::mem::take(&mut self.scratch);
+        let [in_r, ..] = &mut scratch;
+        self.process_mono_input(input, in_r, out_l, out_r, num_samples);
+        self.scratch = scratch;
+    }
+
+    /// Processes a stereo signal into mono output.
+    /// The output channels are averaged, which keeps the level of the dry signal,
+    /// while the uncorrelated reverb tails of both channels sum to about 3 dB less
+    /// than in stereo, without comb filtering.
+    /// All buffers must be at least `num_samples` long.
+    /// Panics if `num_samples` is greater than `max_block_size`.
+    pub fn process_stereo_to_mono(
+        &mut self,
+        in_l: &[S],
+        in_r: &[S],
+        output: &mut [S],
+        num_samples: u32,
+    ) {
+        assert!(num_samples <= self.max_block_size);
+        let n = num_samples as usize;
+        let mut scratch = std::mem::take(&mut self.scratch);
+        let [_, _, out_l, out_r] = &mut scratch;
+        self.process(in_l, in_r, &mut out_l[..n], &mut out_r[..n], num_samples);
+        sum_to_mono(&out_l[..n], &out_r[..n], &mut output[..n]);
+        self.scratch = scratch;
+    }
+
+    /// Processes a mono signal into mono output,
+    /// combining [process_mono_to_stereo](Self::process_mono_to_stereo)
+    /// and [process_stereo_to_mono](Self::process_stereo_to_mono).
+    /// Both buffers must be at least `num_samples` long.
+    /// Panics if `num_samples` is greater than `max_block_size`.
+    pub fn process_mono(&mut self, input: &[S], output: &mut [S], num_samples: u32) {
+        assert!(num_samples <= self.max_block_size);
+        let n = num_samples as usize;
+        let mut scratch = std::mem::take(&mut self.scratch);
+        let [in_r, _, out_l, out_r] = &mut scratch;
+        self.process_mono_input(input, in_r, &mut out_l[..n], &mut out_r[..n], num_samples);
+        sum_to_mono(&out_l[..n], &out_r[..n], &mut output[..n]);
+        self.scratch = scratch;
+    }
+
+    /// Processes a mono input, using `in_r` for the decorrelated copy if needed.
+    fn process_mono_input(
+        &mut self,
+        input: &[S],
+        in_r: &mut [S],
+        out_l: &mut [S],
+        out_r: &mut [S],
+        num_samples: u32,
+    ) {
+        match self.mono_input {
+            MonoInput::Duplicate => self.process(input, input, out_l, out_r, num_samples),
+            MonoInput::Decorrelated => {
+                let n = num_samples as usize;
+                self.decorrelator.process(&input[..n], &mut in_r[..n]);
+                // the decorrelated copy only feeds the wet signal,
+                // the dry signal stays the same on both channels
+                self.set_mono_dry(true);
+                self.process(input, &in_r[..n], out_l, out_r, num_samples);
+                self.set_mono_dry(false);
+            }
+        }
+    }
+
+    /// Makes the engines take the dry signal of both channels from the left input.
+    fn set_mono_dry(&mut self, enabled: bool) {
+        self.inner.as_mut().unwrap().set_mono_dry(enabled);
+        if let Some(outgoing) = self.crossfade.outgoing.as_mut() {
+            outgoing.set_mono_dry(enabled);
+        }
+    }
+
+    /// Sets how the mono processing methods feed their input into the reverb.
+    /// The default is [MonoInput::Duplicate].
+    pub fn set_mono_input(&mut self, mode: MonoInput) {
+        self.mono_input = mode;
+    }
+
+    /// Returns how the mono processing methods feed their input into the reverb.
+    pub fn mono_input(&self) -> MonoInput {
+        self.mono_input
+    }
+
+    /// Processes stereo audio of any length in any [Sample](crate::Sample) format and layout,
+    /// converting it to and from planar buffers of the engine's sample type without allocating.
+    /// See [InputBuffer] and [OutputBuffer] for the supported layouts.
+    /// Input and output must have the same number of frames.
+    ///
+    /// ```
+    /// # use cloudseedcore_rs::{Interleaved, ReverbController};
+    /// let mut reverb = ReverbController::new(48000.0, 512);
+    /// let input = [[0i16; 1000], [0i16; 1000]];
+    /// let mut output = vec![0i16; 2000];
+    /// reverb.process_buffers(&[&input[0][..], &input[1][..]], &mut Interleaved(&mut output[..]));
+    /// ```
+    pub fn process_buffers<I, O>(&mut self, input: &I, output: &mut O)
+    where
+        I: InputBuffer + ?Sized,
+        O: OutputBuffer + ?Sized,
+    {
+        let len = input.frames();
+        assert!(output.frames() == len, "input and output lengths differ");
+
+        let mut scratch = std::mem::take(&mut self.scratch);
+        let [in_l, in_r, out_l, out_r] = &mut scratch;
+        let block = self.max_block_size.max(1) as usize;
+        for start in (0..len).step_by(block) {
+            let n = block.min(len - start);
+            input.read_frames(start, &mut in_l[..n], &mut in_r[..n]);
+            self.process(
+                &in_l[..n],
+                &in_r[..n],
+                &mut out_l[..n],
+                &mut out_r[..n],
+                n as u32,
+            );
+            output.write_frames(start, &out_l[..n], &out_r[..n]);
+        }
+        self.scratch = scratch;
+    }
+
+    /// Processes a stereo signal like [process](Self::process),
+    /// additionally writing the dry, early and late signals to separate buffers
+    /// so they can be routed independently.
+    /// All buffers must be at least `num_samples` long.
+    /// Panics if `num_samples` is greater than `max_block_size`.
+    pub fn process_buses(
+        &mut self,
+        in_l: &[S],
+        in_r: &[S],
+        out_l: &mut [S],
+        out_r: &mut [S],
+        buses: &mut ReverbBuses<S>,
+        num_samples: u32,
+    ) {
+        assert!(buses.fits(num_samples));
+        self.process_smoothed(in_l, in_r, out_l, out_r, Some(buses), num_samples);
+    }
+
+    fn process_smoothed(
+        &mut self,
+        in_l: &[S],
+        in_r: &[S],
+        out_l: &mut [S],
+        out_r: &mut [S],
+        buses: Option<&mut ReverbBuses<S>>,
+        num_samples: u32,
+    ) {
+        let Some(mut metering) = self.metering.take() else {
+            self.process_block(in_l, in_r, out_l, out_r, buses, num_samples);
+            return;
+        };
+
+        // the early and late signals are captured internally if the caller doesn't need them
+        let (mut state, mut own_buses) = metering.split();
+        match buses {
+            Some(buses) => {
+                self.process_metered(in_l, in_r, out_l, out_r, buses, &mut state, num_samples)
+            }
+            None => self.process_metered(
+                in_l,
+                in_r,
+                out_l,
+                out_r,
+                &mut own_buses,
+                &mut state,
+                num_samples,
+            ),
+        }
+        self.metering = Some(metering);
+    }
+
+    /// Processes a block like [process_block](Self::process_block) and measures its levels.
+    #[allow(clippy::too_many_arguments)]
+    fn process_metered(
+        &mut self,
+        in_l: &[S],
+        in_r: &[S],
+        out_l: &mut [S],
+        out_r: &mut [S],
+        buses: &mut ReverbBuses<S>,
+        state: &mut MeteringState,
+        num_samples: u32,
+    ) {
+        self.process_block(in_l, in_r, out_l, out_r, Some(buses), num_samples);
+
+        let n = num_samples as usize;
+        let signals = [
+            [&in_l[..n], &in_r[..n]],
+            [&buses.early_l[..n], &buses.early_r[..n]],
+            [&buses.late_l[..n], &buses.late_r[..n]],
+            [&out_l[..n], &out_r[..n]],
+        ];
+        state.measure(signals, &self.meters);
+    }
+
+    /// Processes a block, smoothing parameters and skipping silence.
+    fn process_block(
+        &mut self,
+        in_l: &[S],
+        in_r: &[S],
+        out_l: &mut [S],
+        out_r: &mut [S],
+        buses: Option<&mut ReverbBuses<S>>,
         num_samples: u32,
     ) {
         assert!(num_samples <= self.max_block_size);
@@ -47,33 +633,353 @@ impl ReverbController {
         assert!(out_l.len() >= num_samples as usize);
         assert!(out_r.len() >= num_samples as usize);
 
-        self.inner
-            .as_mut()
-            .unwrap()
-            .process(in_l, in_r, out_l, out_r, num_samples);
+        self.apply_shared_changes();
+
+        let n = num_samples as usize;
+        if self.silence.is_sleeping() {
+            if self.silence.is_quiet(&in_l[..n]) && self.silence.is_quiet(&in_r[..n]) {
+                out_l[..n].fill(S::default());
+                out_r[..n].fill(S::default());
+                if let Some(b) = buses {
+                    b.dry_l[..n].fill(S::default());
+                    b.dry_r[..n].fill(S::default());
+                    b.early_l[..n].fill(S::default());
+                    b.early_r[..n].fill(S::default());
+                    b.late_l[..n].fill(S::default());
+                    b.late_r[..n].fill(S::default());
+                }
+                self.advance_smoothers(num_samples);
+                return;
+            }
+            self.silence.wake();
+        }
+
+        if !self.silence.is_enabled() {
+            self.process_ramped(in_l, in_r, out_l, out_r, buses, num_samples);
+            return;
+        }
+
+        // the early and late signals are captured internally if the caller doesn't need them,
+        // so a tail that is muted or attenuated by the output levels keeps the reverb awake
+        match buses {
+            Some(buses) => self.process_detecting(in_l, in_r, out_l, out_r, buses, num_samples),
+            None => {
+                let mut own = std::mem::take(&mut self.silence_buses);
+                let [dry_l, dry_r, early_l, early_r, late_l, late_r] = &mut own;
+                let mut buses = ReverbBuses {
+                    dry_l,
+                    dry_r,
+                    early_l,
+                    early_r,
+                    late_l,
+                    late_r,
+                };
+                self.process_detecting(in_l, in_r, out_l, out_r, &mut buses, num_samples);
+                self.silence_buses = own;
+            }
+        }
+    }
+
+    /// Processes a block and counts it towards silence detection.
+    fn process_detecting(
+        &mut self,
+        in_l: &[S],
+        in_r: &[S],
+        out_l: &mut [S],
+        out_r: &mut [S],
+        buses: &mut ReverbBuses<S>,
+        num_samples: u32,
+    ) {
+        self.process_ramped(in_l, in_r, out_l, out_r, Some(buses), num_samples);
+
+        let n = num_samples as usize;
+        // a frozen or crossfading tail may still come back
+        let can_sleep = !self.frozen && !self.crossfade.is_active() && !self.is_smoothing();
+        let quiet = can_sleep
+            && [
+                &in_l[..n],
+                &in_r[..n],
+                &out_l[..n],
+                &out_r[..n],
+                &buses.early_l[..n],
+                &buses.early_r[..n],
+                &buses.late_l[..n],
+                &buses.late_r[..n],
+            ]
+            .iter()
+            .all(|x| self.silence.is_quiet(x));
+        let inner = &self.inner;
+        self.silence.advance(quiet, num_samples, || {
+            inner.as_ref().unwrap().propagation_samples()
+        });
+    }
+
+    /// Processes a block, in short sub-blocks while parameters are being smoothed.
+    fn process_ramped(
+        &mut self,
+        in_l: &[S],
+        in_r: &[S],
+        out_l: &mut [S],
+        out_r: &mut [S],
+        mut buses: Option<&mut ReverbBuses<S>>,
+        num_samples: u32,
+    ) {
+        // while parameters are being smoothed, process in short
+        // sub-blocks and update the ramped values in between
+        let mut pos = 0;
+        while pos < num_samples && self.is_smoothing() {
+            let len = SMOOTHING_INTERVAL.min(num_samples - pos);
+            self.advance_smoothers(len);
+            self.process_unchecked(
+                in_l,
+                in_r,
+                out_l,
+                out_r,
+                buses.as_deref_mut(),
+                pos,
+                pos + len,
+            );
+            pos += len;
+        }
+
+        if pos < num_samples {
+            self.process_unchecked(in_l, in_r, out_l, out_r, buses, pos, num_samples);
+        }
+    }
+
+    /// Processes the samples in range `start..end` of the given buffers
+    /// without applying parameter smoothing.
+    #[allow(clippy::too_many_arguments)]
+    fn process_unchecked(
+        &mut self,
+        in_l: &[S],
+        in_r: &[S],
+        out_l: &mut [S],
+        out_r: &mut [S],
+        mut buses: Option<&mut ReverbBuses<S>>,
+        start: u32,
+        end: u32,
+    ) {
+        let (s, e) = (start as usize, end as usize);
+        let inner = self.inner.as_mut().unwrap();
+        match buses.as_deref_mut() {
+            None => inner.process(
+                &in_l[s..e],
+                &in_r[s..e],
+                &mut out_l[s..e],
+                &mut out_r[s..e],
+                end - start,
+            ),
+            Some(b) => inner.process_buses(
+                &in_l[s..e],
+                &in_r[s..e],
+                &mut out_l[s..e],
+                &mut out_r[s..e],
+                &mut b.dry_l[s..e],
+                &mut b.dry_r[s..e],
+                &mut b.early_l[s..e],
+                &mut b.early_r[s..e],
+                &mut b.late_l[s..e],
+                &mut b.late_r[s..e],
+                end - start,
+            ),
+        }
+
+        let fade = &mut self.crossfade;
+        if !fade.is_active() {
+            return;
+        }
+
+        let n = e - s;
+        let (position, length) = (fade.position, fade.length);
+        let outgoing = fade.outgoing.as_mut().unwrap();
+        let [l, r, dry_l, dry_r, early_l, early_r, late_l, late_r] = &mut fade.bufs;
+        match buses {
+            None => outgoing.process(
+                &in_l[s..e],
+                &in_r[s..e],
+                &mut l[..n],
+                &mut r[..n],
+                end - start,
+            ),
+            Some(b) => {
+                outgoing.process_buses(
+                    &in_l[s..e],
+                    &in_r[s..e],
+                    &mut l[..n],
+                    &mut r[..n],
+                    &mut dry_l[..n],
+                    &mut dry_r[..n],
+                    &mut early_l[..n],
+                    &mut early_r[..n],
+                    &mut late_l[..n],
+                    &mut late_r[..n],
+                    end - start,
+                );
+                crossfade(&mut b.dry_l[s..e], dry_l, position, length);
+                crossfade(&mut b.dry_r[s..e], dry_r, position, length);
+                crossfade(&mut b.early_l[s..e], early_l, position, length);
+                crossfade(&mut b.early_r[s..e], early_r, position, length);
+                crossfade(&mut b.late_l[s..e], late_l, position, length);
+                crossfade(&mut b.late_r[s..e], late_r, position, length);
+            }
+        }
+        crossfade(&mut out_l[s..e], l, position, length);
+        crossfade(&mut out_r[s..e], r, position, length);
+
+        fade.position = (position + end - start).min(length);
+    }
+
+    /// Processes a stereo signal, applying the given events at their sample offsets.
+    /// The block is split at each event, so parameter changes and program loads
+    /// take effect on the exact sample they are scheduled for.
+    /// All buffers must be at least `num_samples` long.
+    /// Panics if `num_samples` is greater than `max_block_size`,
+    /// if the events are not sorted by sample offset,
+    /// or if an event's sample offset is not less than `num_samples`.
+    pub fn process_with_events(
+        &mut self,
+        in_l: &[S],
+        in_r: &[S],
+        out_l: &mut [S],
+        out_r: &mut [S],
+        num_samples: u32,
+        events: &[ReverbEvent],
+    ) {
+        assert!(num_samples <= self.max_block_size);
+        assert!(
+            events
+                .windows(2)
+                .all(|w| w[0].sample_offset() <= w[1].sample_offset())
+        );
+        assert!(events.iter().all(|e| e.sample_offset() < num_samples));
+
+        // apply changes from other threads first, so they don't override events at offset 0
+        self.apply_shared_changes();
+
+        let mut pos = 0;
+        for event in events {
+            let offset = event.sample_offset();
+            if offset > pos {
+                self.process_range(in_l, in_r, out_l, out_r, pos, offset);
+                pos = offset;
+            }
+
+            match event {
+                ReverbEvent::Parameter { id, value, .. } => self.set_parameter(*id, *value),
+                ReverbEvent::Program { program, .. } => self.set_program(program),
+            }
+        }
+
+        self.process_range(in_l, in_r, out_l, out_r, pos, num_samples);
+    }
+
+    /// Processes the samples in range `start..end` of the given buffers.
+    fn process_range(
+        &mut self,
+        in_l: &[S],
+        in_r: &[S],
+        out_l: &mut [S],
+        out_r: &mut [S],
+        start: u32,
+        end: u32,
+    ) {
+        let (s, e) = (start as usize, end as usize);
+        self.process(
+            &in_l[s..e],
+            &in_r[s..e],
+            &mut out_l[s..e],
+            &mut out_r[s..e],
+            end - start,
+        );
     }
 
     /// Clears internal buffers, ending any ongoing reverb tail.
+    /// Cancels any crossfade in progress.
     pub fn reset(&mut self) {
+        self.crossfade.cancel();
         self.inner.as_mut().unwrap().reset();
+        self.decorrelator.reset();
+        self.silence.reset();
     }
 
     /// Updates the reverb's sample rate in Hz.
+    /// Panics if the sample rate is not supported, like [new](ReverbController::new),
+    /// or the underlying C++ reverb fails to reconfigure.
+    /// Use [try_set_sample_rate](Self::try_set_sample_rate) for validated, fallible updates.
     pub fn set_sample_rate(&mut self, sample_rate: f32) {
-        self.inner.as_mut().unwrap().set_sample_rate(sample_rate);
+        assert_sample_rate(sample_rate);
+        self.update_sample_rate(sample_rate)
+            .expect("failed to update CloudSeedCore sample rate");
+    }
+
+    /// Updates the reverb's sample rate like [set_sample_rate](Self::set_sample_rate),
+    /// but validates it the same way as [try_new](Self::try_new)
+    /// and reports failures instead of panicking.
+    pub fn try_set_sample_rate(&mut self, sample_rate: f32) -> Result<(), Error> {
+        validate_sample_rate(sample_rate)?;
+        self.update_sample_rate(sample_rate)
+    }
+
+    /// Returns the reverb's sample rate in Hz.
+    pub fn get_sample_rate(&self) -> f32 {
+        self.inner.as_ref().unwrap().get_sample_rate()
+    }
+
+    fn update_sample_rate(&mut self, sample_rate: f32) -> Result<(), Error> {
+        self.inner.as_mut().unwrap().set_sample_rate(sample_rate)?;
+        if let Some(outgoing) = self.crossfade.outgoing.as_mut() {
+            outgoing.set_sample_rate(sample_rate)?;
+        }
+        self.decorrelator.set_sample_rate(sample_rate);
+        if let Some(metering) = &mut self.metering {
+            metering.set_sample_rate(sample_rate);
+        }
+        self.params
+            .set_auto_gain_rate(self.auto_gain.then_some(sample_rate));
+        self.update_auto_gain(false);
+        Ok(())
     }
 
     /// Applies a normalized parameter value in range 0..1
     /// to the parameter with the given id.
+    /// If smoothing is enabled and the parameter [is continuous](ParamId::is_continuous),
+    /// the value is ramped towards during the following `process` calls.
+    /// Values outside 0..1 are clamped, and NaN is treated as 0.
     pub fn set_parameter(&mut self, id: ParamId, value: f32) {
+        let value = clamp_parameter(value);
+        self.params.store(id.into(), value);
+        self.apply_parameter(id, value);
+        if id == ParamId::LateLineCount {
+            self.update_auto_gain(true);
+        }
+    }
+
+    fn apply_parameter(&mut self, id: ParamId, value: f32) {
+        let ramp_samples = if id.is_continuous() {
+            self.ramp_samples()
+        } else {
+            0
+        };
+
         let id: u8 = id.into();
-        self.inner.as_mut().unwrap().set_parameter(id as u32, value);
+        let smoother = &mut self.smoothers[id as usize];
+        smoother.set_target(value, ramp_samples, self.smoothing_mode);
+        if !smoother.is_active() {
+            self.inner.as_mut().unwrap().set_parameter(id as u32, value);
+        }
     }
 
     /// Returns the normalized parameter value in range 0..1 for the given parameter id.
+    /// For a parameter that is currently being smoothed, this is the value it is ramping towards.
     pub fn get_parameter(&self, id: ParamId) -> f32 {
         let id: u8 = id.into();
-        self.inner.as_ref().unwrap().get_parameter(id as u32)
+        let smoother = &self.smoothers[id as usize];
+        if smoother.is_active() {
+            smoother.target()
+        } else {
+            self.inner.as_ref().unwrap().get_parameter(id as u32)
+        }
     }
 
     /// Returns a snapshot of all current parameter values.
@@ -82,24 +988,529 @@ impl ReverbController {
         // fill a temporary array using the cxx bridge, then convert to typed Program
         let mut vals = [0.0f32; 45];
         self.inner.as_ref().unwrap().get_all_parameters(&mut vals);
+        for (val, smoother) in vals.iter_mut().zip(&self.smoothers) {
+            if smoother.is_active() {
+                *val = smoother.target();
+            }
+        }
         Program::from_array(vals)
     }
 
     /// Loads a program, ending any ongoing reverb tail.
+    /// Parameters are applied immediately, without smoothing.
+    /// Cancels any crossfade in progress.
     pub fn set_program(&mut self, program: &Program) {
+        self.params.store_program(program);
+        self.load_program(program);
+        self.update_auto_gain(false);
+    }
+
+    fn load_program(&mut self, program: &Program) {
         let params = program.to_array();
+        for (smoother, &value) in self.smoothers.iter_mut().zip(&params) {
+            smoother.reset(value);
+        }
+        self.crossfade.cancel();
         self.inner.as_mut().unwrap().load_program(&params);
     }
+
+    /// Loads a program while preserving the ongoing reverb tail.
+    /// The outgoing program keeps running in parallel to the new one,
+    /// and the outputs are crossfaded linearly over `duration` seconds.
+    ///
+    /// The first crossfade allocates a second reverb engine, which is reused afterwards.
+    /// If that fails, the program is loaded without a crossfade.
+    /// Starting a crossfade while another one is in progress
+    /// cuts off the older outgoing program.
+    pub fn set_program_crossfade(&mut self, program: &Program, duration: f32) {
+        let sample_rate = self.inner.as_ref().unwrap().get_sample_rate();
+        let length = (duration.max(0.0) * sample_rate) as u32;
+        // a sleeping reverb has no tail left to fade out
+        if length == 0 || self.silence.is_sleeping() {
+            self.set_program(program);
+            return;
+        }
+
+        if !self.prepare_spare_engine() {
+            self.set_program(program);
+            return;
+        }
+
+        self.params.store_program(program);
+        let fade = &mut self.crossfade;
+
+        // the spare engine takes on the new program and becomes the active one,
+        // while the previously active engine finishes its tail
+        let params = program.to_array();
+        fade.outgoing.as_mut().unwrap().load_program(&params);
+        fade.outgoing.as_mut().unwrap().set_freeze(self.frozen);
+        std::mem::swap(&mut self.inner, &mut fade.outgoing);
+        fade.length = length;
+        fade.position = 0;
+
+        for (smoother, &value) in self.smoothers.iter_mut().zip(&params) {
+            smoother.reset(value);
+        }
+        // the outgoing engine keeps the compensation of its own program
+        self.update_auto_gain(false);
+    }
+
+    /// Allocates the spare engine used for crossfades if needed,
+    /// and brings it to the current sample rate. Returns whether it is ready.
+    fn prepare_spare_engine(&mut self) -> bool {
+        let sample_rate = self.inner.as_ref().unwrap().get_sample_rate();
+        let fade = &mut self.crossfade;
+        if fade.outgoing.is_null() {
+            // seeded like the active engine, so crossfades render deterministically too
+            match S::Engine::create(sample_rate, self.max_block_size, self.seed) {
+                Ok(engine) => {
+                    fade.outgoing = engine;
+                    for buf in &mut fade.bufs {
+                        *buf = vec![S::default(); self.max_block_size as usize];
+                    }
+                    true
+                }
+                Err(_) => false,
+            }
+        } else {
+            let spare = fade.outgoing.as_mut().unwrap();
+            spare.set_sample_rate(sample_rate).is_ok()
+        }
+    }
+
+    /// Returns whether a crossfade started by [set_program_crossfade](Self::set_program_crossfade)
+    /// is in progress.
+    pub fn is_crossfading(&self) -> bool {
+        self.crossfade.is_active()
+    }
+
+    /// Enables or disables freeze mode.
+    /// While frozen, the late reverb tail sustains indefinitely:
+    /// the delay lines' loop gain is raised to unity, their damping filters
+    /// and modulation are bypassed, and new input is no longer fed into them.
+    /// Dry signal and early reflections are unaffected.
+    /// Engaging and releasing freeze is ramped to avoid clicks.
+    pub fn set_freeze(&mut self, enabled: bool) {
+        self.frozen = enabled;
+        self.inner.as_mut().unwrap().set_freeze(enabled);
+        if let Some(outgoing) = self.crossfade.outgoing.as_mut() {
+            outgoing.set_freeze(enabled);
+        }
+    }
+
+    /// Returns whether freeze mode is enabled.
+    pub fn is_frozen(&self) -> bool {
+        self.frozen
+    }
+
+    /// Returns the estimated number of samples the output takes to decay below
+    /// [DEFAULT_TAIL_THRESHOLD_DB] after the input stops,
+    /// computed from the current program with [Program::estimated_tail].
+    /// Includes the remaining duration of a crossfade in progress.
+    /// Returns `u32::MAX` while frozen, as the tail then sustains indefinitely.
+    pub fn tail_samples(&self) -> u32 {
+        if self.frozen {
+            return u32::MAX;
+        }
+        let tail = self
+            .get_program()
+            .estimated_tail(self.get_sample_rate(), DEFAULT_TAIL_THRESHOLD_DB);
+        let fade = &self.crossfade;
+        tail.max(fade.length - fade.position)
+    }
+
+    /// Enables silence detection with the given threshold in dBFS, or disables it with `None`,
+    /// which is the default.
+    ///
+    /// Once input and output, as well as the early and late signals before their output
+    /// levels, have stayed below the threshold for longer than a signal takes to pass
+    /// through the reverb, the tail has decayed and the reverb goes to sleep:
+    /// processing only writes zeros, skipping all DSP work, until the input exceeds
+    /// the threshold again. Sleep is never entered while frozen, crossfading
+    /// or ramping parameters. See [is_silent](Self::is_silent).
+    ///
+    /// Enabling allocates buffers for capturing the early and late signals.
+    pub fn set_silence_threshold(&mut self, threshold_db: Option<f32>) {
+        if threshold_db.is_some() && self.silence_buses[0].is_empty() {
+            let len = self.max_block_size as usize;
+            self.silence_buses = std::array::from_fn(|_| vec![S::default(); len]);
+        }
+        self.silence.set_threshold_db(threshold_db);
+    }
+
+    /// Returns the silence detection threshold in dBFS, or `None` if detection is disabled.
+    pub fn silence_threshold(&self) -> Option<f32> {
+        self.silence.threshold_db()
+    }
+
+    /// Returns whether the reverb is silent and sleeping,
+    /// see [set_silence_threshold](Self::set_silence_threshold).
+    /// Always false while silence detection is disabled.
+    pub fn is_silent(&self) -> bool {
+        self.silence.is_sleeping()
+    }
+
+    /// Enables or disables auto gain, which is disabled by default.
+    ///
+    /// While enabled, the wet signal is scaled by the
+    /// [loudness compensation](Program::loudness_compensation) of the current program,
+    /// so that programs sound equally loud when switching between them.
+    /// The compensation is measured whenever a program is loaded or `LateLineCount` changes,
+    /// always with the `EarlyOut` and `LateOut` levels the program was loaded with,
+    /// so that other parameters, including these two, change the level as usual.
+    /// A `LateLineCount` change ramps the gain over the [smoothing](Self::set_smoothing) time.
+    /// During a crossfade, each program keeps its own compensation.
+    ///
+    /// Measuring renders the program's impulse response, which allocates
+    /// and takes some tens of milliseconds on the thread making the change.
+    /// To keep the audio thread real-time safe, load programs through a [ReverbParams] handle
+    /// on another thread rather than with [set_program](Self::set_program),
+    /// [set_parameter](Self::set_parameter) or [process_with_events](Self::process_with_events).
+    pub fn set_auto_gain(&mut self, enabled: bool) {
+        self.auto_gain = enabled;
+        let sample_rate = self.get_sample_rate();
+        self.params
+            .set_auto_gain_rate(enabled.then_some(sample_rate));
+        self.update_auto_gain(false);
+
+        if self.crossfade.is_active() {
+            let outgoing = self.crossfade.outgoing.as_mut().unwrap();
+            let gain = if enabled {
+                db_to_gain(engine_compensation(outgoing.as_ref().get_ref()))
+            } else {
+                1.0
+            };
+            outgoing.set_wet_gain(gain);
+        }
+    }
+
+    /// Returns whether auto gain is enabled.
+    pub fn is_auto_gain(&self) -> bool {
+        self.auto_gain
+    }
+
+    /// Returns the gain in dB that auto gain applies to the wet signal of the current program,
+    /// or 0 while auto gain is disabled.
+    pub fn auto_gain_db(&self) -> f32 {
+        self.auto_gain_db
+    }
+
+    /// Measures the loudness compensation of the current program while auto gain is enabled,
+    /// and applies it to the active engine.
+    /// The program is measured with the output levels it was loaded with,
+    /// so changes to `EarlyOut` and `LateOut` made since keep their effect.
+    fn update_auto_gain(&mut self, ramp: bool) {
+        let compensation = if self.auto_gain {
+            self.params
+                .auto_gain_program()
+                .loudness_compensation(self.get_sample_rate())
+        } else {
+            0.0
+        };
+        self.apply_auto_gain(compensation, ramp);
+    }
+
+    /// Applies a loudness compensation to the active engine. With `ramp`, the gain is smoothed
+    /// like a continuous parameter, otherwise it changes immediately, e.g. on program loads.
+    fn apply_auto_gain(&mut self, compensation: f32, ramp: bool) {
+        self.auto_gain_db = compensation;
+        let gain = db_to_gain(compensation);
+        let ramp_samples = if ramp { self.ramp_samples() } else { 0 };
+        self.wet_gain
+            .set_target(gain, ramp_samples, self.smoothing_mode);
+        if !self.wet_gain.is_active() {
+            self.inner.as_mut().unwrap().set_wet_gain(gain);
+        }
+    }
+
+    /// Captures the complete internal state of the reverb: parameters, delay and allpass
+    /// buffers, filter states, modulation phases, parameter ramps, freeze and crossfade state.
+    /// The snapshot can be loaded with [restore](Self::restore), also into another instance,
+    /// which then continues exactly where this one was.
+    /// Allocates memory for the snapshot, which is several megabytes large.
+    pub fn snapshot(&self) -> ReverbSnapshot {
+        let mut w = SnapshotWriter::new();
+        w.f32(self.inner.as_ref().unwrap().get_sample_rate());
+        w.u8(size_of::<S>() as u8);
+        w.u8(self.frozen as u8);
+        for smoother in &self.smoothers {
+            smoother.write_state(&mut w);
+        }
+        for level in self.params.loaded_levels() {
+            w.f32(level);
+        }
+        write_engine(&mut w, self.inner.as_ref().unwrap());
+        self.decorrelator.write_state(&mut w);
+        self.silence.write_state(&mut w);
+
+        let fade = &self.crossfade;
+        w.u8(fade.is_active() as u8);
+        if fade.is_active() {
+            w.u32(fade.length);
+            w.u32(fade.position);
+            write_engine(&mut w, fade.outgoing.as_ref().unwrap());
+        }
+        w.finish()
+    }
+
+    /// Restores the state captured by [snapshot](Self::snapshot),
+    /// switching to the snapshot's sample rate if it differs.
+    /// The smoothing settings, [mono input](Self::set_mono_input) mode,
+    /// [silence threshold](Self::set_silence_threshold), [auto gain](Self::set_auto_gain),
+    /// seed and maximum block size are left unchanged,
+    /// and changes pending on [ReverbParams] handles are applied afterwards as usual.
+    ///
+    /// Returns [Error::InvalidSnapshot] if the snapshot is corrupted, was taken
+    /// by a different version of this crate or on a different platform,
+    /// or by a reverb processing a different sample type.
+    /// The reverb may have been reset in that case.
+    pub fn restore(&mut self, snapshot: &ReverbSnapshot) -> Result<(), Error> {
+        let mut r = SnapshotReader::new(snapshot.as_bytes())?;
+        let sample_rate = r.f32()?;
+        validate_sample_rate(sample_rate).map_err(|_| Error::InvalidSnapshot)?;
+        if r.u8()? as usize != size_of::<S>() {
+            return Err(Error::InvalidSnapshot);
+        }
+        let frozen = r.bool()?;
+        let mut smoothers = [ParamSmoother::default(); 45];
+        for smoother in &mut smoothers {
+            *smoother = ParamSmoother::read_state(&mut r)?;
+        }
+        let loaded_levels = [r.f32_in(0.0, 1.0)?, r.f32_in(0.0, 1.0)?];
+        let (params, state) = read_engine(&mut r)?;
+        let decorrelator = Decorrelator::read_state(&mut r, sample_rate)?;
+        let silence = self.silence.read_state(&mut r)?;
+        let crossfade = if r.bool()? {
+            let (length, position) = (r.u32()?, r.u32()?);
+            if position >= length {
+                return Err(Error::InvalidSnapshot);
+            }
+            Some((length, position, read_engine(&mut r)?))
+        } else {
+            None
+        };
+        r.finish()?;
+
+        if sample_rate != self.inner.as_ref().unwrap().get_sample_rate() {
+            self.update_sample_rate(sample_rate)?;
+        }
+
+        if !self.inner.as_mut().unwrap().load_state(&params, state) {
+            return Err(Error::InvalidSnapshot);
+        }
+        self.crossfade.cancel();
+        self.smoothers = smoothers;
+        self.frozen = frozen;
+        self.decorrelator = decorrelator;
+        self.silence = silence;
+        self.params.store_program(&self.get_program());
+        self.params.set_loaded_levels(loaded_levels);
+        self.update_auto_gain(false);
+
+        if let Some((length, position, (params, state))) = crossfade {
+            if !self.prepare_spare_engine() {
+                return Err(Error::Cxx("failed to create CloudSeedCore reverb".into()));
+            }
+            let fade = &mut self.crossfade;
+            if !fade.outgoing.as_mut().unwrap().load_state(&params, state) {
+                return Err(Error::InvalidSnapshot);
+            }
+            fade.length = length;
+            fade.position = position;
+            let outgoing = fade.outgoing.as_mut().unwrap();
+            let gain = if self.auto_gain {
+                db_to_gain(engine_compensation(outgoing.as_ref().get_ref()))
+            } else {
+                1.0
+            };
+            outgoing.set_wet_gain(gain);
+        }
+        Ok(())
+    }
+
+    /// Creates a copy of this reverb that continues the exact same tail,
+    /// unlike [clone](Clone::clone), which starts with empty buffers.
+    /// The fork has the same seed, smoothing settings, mono input mode, silence threshold
+    /// and auto gain setting, and its own [ReverbParams] handle.
+    pub fn fork(&self) -> Self {
+        let sample_rate = self.inner.as_ref().unwrap().get_sample_rate();
+        let mut forked = Self::create(sample_rate, self.max_block_size, self.seed);
+        forked.set_smoothing(self.smoothing_time, self.smoothing_mode);
+        forked.set_mono_input(self.mono_input);
+        forked.set_silence_threshold(self.silence_threshold());
+        forked.set_metering(self.is_metering());
+        forked.set_auto_gain(self.auto_gain);
+        forked
+            .restore(&self.snapshot())
+            .expect("failed to restore snapshot");
+        forked
+    }
+
+    /// Sets the time in seconds over which changes to
+    /// [continuous](ParamId::is_continuous) parameters are ramped,
+    /// and the shape of the ramp.
+    /// A time of zero disables smoothing, which is the default.
+    /// Ramps that are already in progress are not affected.
+    pub fn set_smoothing(&mut self, time: f32, mode: SmoothingMode) {
+        self.smoothing_time = time.max(0.0);
+        self.smoothing_mode = mode;
+    }
+
+    /// Returns the parameter smoothing time in seconds.
+    pub fn smoothing_time(&self) -> f32 {
+        self.smoothing_time
+    }
+
+    /// Returns the parameter smoothing curve.
+    pub fn smoothing_mode(&self) -> SmoothingMode {
+        self.smoothing_mode
+    }
+
+    /// Returns whether any parameter is currently being ramped.
+    fn is_smoothing(&self) -> bool {
+        self.smoothers.iter().any(|s| s.is_active()) || self.wet_gain.is_active()
+    }
+
+    /// Returns the length of a parameter ramp in samples for the current smoothing time.
+    fn ramp_samples(&self) -> u32 {
+        (self.smoothing_time * self.inner.as_ref().unwrap().get_sample_rate()) as u32
+    }
+
+    /// Applies changes made through [ReverbParams] handles since the last call.
+    fn apply_shared_changes(&mut self) {
+        let changes = self.params.take_changes();
+        if changes.is_empty() {
+            return;
+        }
+
+        if changes.program() {
+            // the program already contains the latest individual parameter values
+            let program = self.params.get_program();
+            self.load_program(&program);
+        } else {
+            for id in changes.parameters() {
+                let value = self.params.get_parameter(id);
+                self.apply_parameter(id, value);
+            }
+        }
+
+        // the handle measured the compensation when the change was made
+        let line_count = changes.parameters().any(|id| id == ParamId::LateLineCount);
+        if self.auto_gain && (changes.program() || line_count) {
+            self.apply_auto_gain(self.params.compensation(), !changes.program());
+        }
+
+        if changes.reset() {
+            self.reset();
+        }
+    }
+
+    /// Advances all active parameter ramps and applies their new values.
+    fn advance_smoothers(&mut self, samples: u32) {
+        for (id, smoother) in self.smoothers.iter_mut().enumerate() {
+            if smoother.is_active() {
+                let value = smoother.advance(samples);
+                self.inner.as_mut().unwrap().set_parameter(id as u32, value);
+            }
+        }
+        if self.wet_gain.is_active() {
+            let gain = self.wet_gain.advance(samples);
+            self.inner.as_mut().unwrap().set_wet_gain(gain);
+        }
+    }
 }
 
-impl Clone for ReverbController {
-    /// Creates a new underlying C++ ReverbController and reapplies all parameters.
+impl<S: EngineSample> Clone for ReverbController<S> {
+    /// Creates a new underlying C++ ReverbController with the same seed
+    /// and reapplies all parameters.
+    /// The clone has its own [ReverbParams] handle.
     fn clone(&self) -> Self {
         let sample_rate = self.inner.as_ref().unwrap().get_sample_rate();
-        let mut cloned = ReverbController::new(sample_rate, self.max_block_size);
+        let mut cloned = Self::create(sample_rate, self.max_block_size, self.seed);
+        cloned.set_smoothing(self.smoothing_time, self.smoothing_mode);
+        cloned.set_mono_input(self.mono_input);
+        cloned.set_silence_threshold(self.silence_threshold());
+        cloned.set_metering(self.is_metering());
+        cloned.set_freeze(self.frozen);
         // apply program parameters from current instance
         let program = self.get_program();
         cloned.set_program(&program);
+        // auto gain measures the program with the output levels it was originally loaded with
+        cloned.params.set_loaded_levels(self.params.loaded_levels());
+        cloned.set_auto_gain(self.auto_gain);
         cloned
     }
 }
+
+/// Crossfades linearly from the `outgoing` signal into `incoming`,
+/// starting `position` samples into a crossfade of `length` samples.
+fn crossfade<S: EngineSample>(incoming: &mut [S], outgoing: &[S], position: u32, length: u32) {
+    for (i, (x, &y)) in incoming.iter_mut().zip(outgoing).enumerate() {
+        let gain = ((position as usize + i) as f32 / length as f32).min(1.0);
+        *x = *x * S::from_f32(gain) + y * S::from_f32(1.0 - gain);
+    }
+}
+
+/// Returns the loudness compensation of the program an engine is running,
+/// see [Program::loudness_compensation].
+fn engine_compensation<S>(engine: &impl Engine<S>) -> f32 {
+    let mut params = [0.0f32; 45];
+    engine.get_all_parameters(&mut params);
+    Program::from_array(params).loudness_compensation(engine.get_sample_rate())
+}
+
+fn db_to_gain(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// Appends an engine's parameters and running state to a snapshot.
+fn write_engine<S>(w: &mut SnapshotWriter, engine: &impl Engine<S>) {
+    let mut params = [0.0f32; 45];
+    engine.get_all_parameters(&mut params);
+    for value in params {
+        w.f32(value);
+    }
+    engine.save_state(w.block(engine.state_size()));
+}
+
+/// Reads an engine's parameters and running state written by [write_engine].
+/// The state is validated when it is loaded.
+fn read_engine<'a>(r: &mut SnapshotReader<'a>) -> Result<([f32; 45], &'a [u8]), Error> {
+    let mut params = [0.0f32; 45];
+    for value in &mut params {
+        *value = r.f32_in(0.0, 1.0)?;
+    }
+    Ok((params, r.block()?))
+}
+
+/// Averages the channels of a stereo signal.
+fn sum_to_mono<S: EngineSample>(left: &[S], right: &[S], output: &mut [S]) {
+    for ((o, &l), &r) in output.iter_mut().zip(left).zip(right) {
+        *o = S::from_f32(0.5) * (l + r);
+    }
+}
+
+/// Returns a seed that differs between calls and process runs.
+fn random_seed() -> u64 {
+    RandomState::new().hash_one(0u8)
+}
+
+/// Checks that the sample rate is one CloudSeedCore can run at.
+fn validate_sample_rate(sample_rate: f32) -> Result<(), Error> {
+    if !sample_rate.is_finite() || sample_rate <= 0.0 {
+        return Err(Error::InvalidSampleRate(sample_rate));
+    }
+    // CloudSeedCore stores the sample rate as an integer
+    if !(MIN_SAMPLE_RATE..=MAX_SAMPLE_RATE).contains(&sample_rate) || sample_rate.fract() != 0.0 {
+        return Err(Error::UnsupportedSampleRate(sample_rate));
+    }
+    Ok(())
+}
+
+/// Panics unless the sample rate passes [validate_sample_rate].
+pub(crate) fn assert_sample_rate(sample_rate: f32) {
+    assert!(
+        validate_sample_rate(sample_rate).is_ok(),
+        "sample_rate must be a whole number in range {MIN_SAMPLE_RATE}..={MAX_SAMPLE_RATE}, got {sample_rate}"
+    );
+}
@@ -0,0 +1,209 @@
+use crate::{ParamId, Program};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+/// Dirty bit signalling that a whole program was written.
+const PROGRAM_PENDING: u64 = 1 << 62;
+/// Dirty bit signalling that a reset was requested.
+const RESET_PENDING: u64 = 1 << 63;
+
+/// Clamps a normalized parameter value to 0..1 and maps NaN to 0,
+/// matching what CloudSeedCore applies.
+pub(crate) fn clamp_parameter(value: f32) -> f32 {
+    if value > 0.0 { value.min(1.0) } else { 0.0 }
+}
+
+/// A lock-free handle to the parameters of a [ReverbController](crate::ReverbController),
+/// obtained through [ReverbController::params](crate::ReverbController::params).
+///
+/// The handle can be cloned and shared with other threads, e.g. a UI thread.
+/// Changes made through it are picked up by the controller
+/// at the start of its next `process` call,
+/// where they behave as if the corresponding controller method had been called.
+/// None of the handle's methods allocate, lock or block, except while
+/// [auto gain](crate::ReverbController::set_auto_gain) is enabled:
+/// then loading a program or changing `LateLineCount` measures the program's loudness
+/// on the calling thread, so the controller doesn't have to.
+#[derive(Clone)]
+pub struct ReverbParams {
+    shared: Arc<Shared>,
+}
+
+struct Shared {
+    /// Normalized parameter values, stored as `f32` bits.
+    values: [AtomicU32; 45],
+    /// Bit `n` is set if parameter `n` changed since the controller last applied it,
+    /// plus [PROGRAM_PENDING] and [RESET_PENDING].
+    dirty: AtomicU64,
+    /// Sample rate to measure [Program::loudness_compensation] at while auto gain is enabled,
+    /// stored as `f32` bits, or 0 while it is disabled.
+    auto_gain_rate: AtomicU32,
+    /// Loudness compensation in dB measured for the last change made through a handle,
+    /// stored as `f32` bits.
+    compensation: AtomicU32,
+    /// `EarlyOut` and `LateOut` of the last loaded program, stored as `f32` bits.
+    /// Auto gain measures with these, so later changes to the output levels keep their effect.
+    loaded_levels: [AtomicU32; 2],
+}
+
+impl ReverbParams {
+    pub(crate) fn new(program: &Program) -> Self {
+        let values = program.to_array().map(|v| AtomicU32::new(v.to_bits()));
+        let loaded_levels =
+            [program.early_out, program.late_out].map(|v| AtomicU32::new(v.to_bits()));
+        Self {
+            shared: Arc::new(Shared {
+                values,
+                dirty: AtomicU64::new(0),
+                auto_gain_rate: AtomicU32::new(0),
+                compensation: AtomicU32::new(0),
+                loaded_levels,
+            }),
+        }
+    }
+
+    /// Schedules a normalized parameter value in range 0..1
+    /// for the parameter with the given id.
+    /// See [ReverbController::set_parameter](crate::ReverbController::set_parameter).
+    pub fn set_parameter(&self, id: ParamId, value: f32) {
+        self.store(id.into(), value);
+        if id == ParamId::LateLineCount {
+            self.measure_compensation();
+        }
+        let id: u8 = id.into();
+        self.shared.dirty.fetch_or(1 << id, Ordering::Release);
+    }
+
+    /// Returns the most recently set normalized value of the given parameter,
+    /// whether it was set through this handle or the controller.
+    pub fn get_parameter(&self, id: ParamId) -> f32 {
+        let id: u8 = id.into();
+        f32::from_bits(self.shared.values[id as usize].load(Ordering::Relaxed))
+    }
+
+    /// Schedules a program load, ending any ongoing reverb tail.
+    /// See [ReverbController::set_program](crate::ReverbController::set_program).
+    /// Parameters set concurrently from another thread may end up in the loaded program.
+    pub fn set_program(&self, program: &Program) {
+        self.store_program(program);
+        self.measure_compensation();
+        self.shared
+            .dirty
+            .fetch_or(PROGRAM_PENDING, Ordering::Release);
+    }
+
+    /// Returns the most recently set values of all parameters.
+    pub fn get_program(&self) -> Program {
+        Program::from_array(std::array::from_fn(|i| {
+            f32::from_bits(self.shared.values[i].load(Ordering::Relaxed))
+        }))
+    }
+
+    /// Schedules a reset, ending any ongoing reverb tail.
+    /// See [ReverbController::reset](crate::ReverbController::reset).
+    pub fn reset(&self) {
+        self.shared.dirty.fetch_or(RESET_PENDING, Ordering::Release);
+    }
+
+    /// Records a value applied directly on the controller, without marking it as dirty.
+    /// The value is clamped like the engine does, see [clamp_parameter].
+    pub(crate) fn store(&self, id: u8, value: f32) {
+        let value = clamp_parameter(value);
+        self.shared.values[id as usize].store(value.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Records a program applied directly on the controller, without marking it as dirty.
+    pub(crate) fn store_program(&self, program: &Program) {
+        for (id, value) in program.to_array().into_iter().enumerate() {
+            self.store(id as u8, value);
+        }
+        self.set_loaded_levels([program.early_out, program.late_out].map(clamp_parameter));
+    }
+
+    /// Returns `EarlyOut` and `LateOut` of the last loaded program.
+    pub(crate) fn loaded_levels(&self) -> [f32; 2] {
+        self.shared
+            .loaded_levels
+            .each_ref()
+            .map(|level| f32::from_bits(level.load(Ordering::Relaxed)))
+    }
+
+    pub(crate) fn set_loaded_levels(&self, levels: [f32; 2]) {
+        for (level, value) in self.shared.loaded_levels.iter().zip(levels) {
+            level.store(value.to_bits(), Ordering::Relaxed);
+        }
+    }
+
+    /// Returns the current program with the output levels it was loaded with,
+    /// whose loudness compensation auto gain applies.
+    pub(crate) fn auto_gain_program(&self) -> Program {
+        let [early_out, late_out] = self.loaded_levels();
+        Program {
+            early_out,
+            late_out,
+            ..self.get_program()
+        }
+    }
+
+    /// Sets the sample rate to measure loudness compensation at,
+    /// or disables measuring it with `None`.
+    pub(crate) fn set_auto_gain_rate(&self, sample_rate: Option<f32>) {
+        let bits = sample_rate.map_or(0, f32::to_bits);
+        self.shared.auto_gain_rate.store(bits, Ordering::Relaxed);
+    }
+
+    /// Returns the loudness compensation in dB measured for the last program
+    /// or `LateLineCount` change made through a handle.
+    pub(crate) fn compensation(&self) -> f32 {
+        f32::from_bits(self.shared.compensation.load(Ordering::Relaxed))
+    }
+
+    /// Measures the loudness compensation of the [auto_gain_program](Self::auto_gain_program)
+    /// if auto gain is enabled.
+    /// Must be called before the change is marked as dirty, which publishes it.
+    fn measure_compensation(&self) {
+        let bits = self.shared.auto_gain_rate.load(Ordering::Relaxed);
+        if bits != 0 {
+            let compensation = self
+                .auto_gain_program()
+                .loudness_compensation(f32::from_bits(bits));
+            self.shared
+                .compensation
+                .store(compensation.to_bits(), Ordering::Relaxed);
+        }
+    }
+
+    /// Takes all changes made through any handle since the last call.
+    pub(crate) fn take_changes(&self) -> Changes {
+        // avoid the read-modify-write on the common path where nothing changed
+        if self.shared.dirty.load(Ordering::Relaxed) == 0 {
+            return Changes(0);
+        }
+        Changes(self.shared.dirty.swap(0, Ordering::Acquire))
+    }
+}
+
+/// Changes taken from a [ReverbParams] handle.
+pub(crate) struct Changes(u64);
+
+impl Changes {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub(crate) fn program(&self) -> bool {
+        self.0 & PROGRAM_PENDING != 0
+    }
+
+    pub(crate) fn reset(&self) -> bool {
+        self.0 & RESET_PENDING != 0
+    }
+
+    /// Returns the ids of the individually changed parameters.
+    pub(crate) fn parameters(&self) -> impl Iterator<Item = ParamId> + '_ {
+        ParamId::ALL
+            .iter()
+            .copied()
+            .filter(|&id| self.0 & (1 << u8::from(id)) != 0)
+    }
+}
@@ -0,0 +1,85 @@
+use crate::snapshot::{SnapshotReader, SnapshotWriter};
+use crate::{Error, Sample};
+
+/// Tracks how long the input and output of a reverb have stayed below a threshold,
+/// and whether processing is suspended because of that.
+/// See [ReverbController::set_silence_threshold](crate::ReverbController::set_silence_threshold).
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct SilenceDetector {
+    /// Threshold in dBFS, or `None` if detection is disabled.
+    threshold_db: Option<f32>,
+    /// Linear amplitude of `threshold_db`.
+    threshold: f64,
+    /// Number of consecutive samples below the threshold.
+    quiet_samples: u32,
+    sleeping: bool,
+}
+
+impl SilenceDetector {
+    pub(crate) fn threshold_db(&self) -> Option<f32> {
+        self.threshold_db
+    }
+
+    /// Sets the threshold in dBFS, `None` disables detection and wakes up the reverb.
+    pub(crate) fn set_threshold_db(&mut self, threshold_db: Option<f32>) {
+        self.threshold_db = threshold_db;
+        match threshold_db {
+            Some(db) => self.threshold = 10f64.powf(db as f64 / 20.0),
+            None => self.wake(),
+        }
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.threshold_db.is_some()
+    }
+
+    pub(crate) fn is_sleeping(&self) -> bool {
+        self.sleeping
+    }
+
+    pub(crate) fn wake(&mut self) {
+        self.sleeping = false;
+        self.quiet_samples = 0;
+    }
+
+    /// Called when the reverb's buffers have been cleared,
+    /// which leaves it silent right away if detection is enabled.
+    pub(crate) fn reset(&mut self) {
+        self.sleeping = self.is_enabled();
+        self.quiet_samples = 0;
+    }
+
+    /// Returns whether all samples are below the threshold.
+    pub(crate) fn is_quiet<S: Sample>(&self, signal: &[S]) -> bool {
+        signal.iter().all(|x| x.to_f64().abs() < self.threshold)
+    }
+
+    /// Counts a processed block of `samples` samples, and starts sleeping once the signal
+    /// has been quiet for longer than `hold` samples, the time it takes to pass through the reverb.
+    pub(crate) fn advance(&mut self, quiet: bool, samples: u32, hold: impl FnOnce() -> u32) {
+        if !quiet {
+            self.quiet_samples = 0;
+            return;
+        }
+        self.quiet_samples = self.quiet_samples.saturating_add(samples);
+        if self.quiet_samples > hold() {
+            self.sleeping = true;
+        }
+    }
+
+    /// Appends the running state, but not the threshold, to a snapshot.
+    pub(crate) fn write_state(&self, w: &mut SnapshotWriter) {
+        w.u8(self.sleeping as u8);
+        w.u32(self.quiet_samples);
+    }
+
+    /// Reads the running state written by [write_state](Self::write_state),
+    /// keeping the threshold of `self`.
+    pub(crate) fn read_state(&self, r: &mut SnapshotReader) -> Result<Self, Error> {
+        Ok(Self {
+            sleeping: r.bool()?,
+            quiet_samples: r.u32()?,
+            ..*self
+        })
+    }
+}
@@ -0,0 +1,127 @@
+use crate::Error;
+use crate::snapshot::{SnapshotReader, SnapshotWriter};
+
+/// Curve used to ramp continuous parameters towards a new value.
+/// See [ReverbController::set_smoothing](crate::ReverbController::set_smoothing).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SmoothingMode {
+    /// Moves towards the new value at a constant rate,
+    /// arriving after the smoothing time.
+    #[default]
+    Linear,
+    /// Approaches the new value exponentially,
+    /// covering 99% of the distance within the smoothing time.
+    Exponential,
+}
+
+/// Distance below which an exponential ramp snaps to its target.
+const SETTLE_THRESHOLD: f32 = 1e-5;
+
+/// Ramps a single normalized parameter value towards its target.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct ParamSmoother {
+    current: f32,
+    target: f32,
+    mode: SmoothingMode,
+    // linear: per-sample increment and number of samples left in the ramp
+    step: f32,
+    remaining: u32,
+    // exponential: per-sample decay of the distance to the target
+    coeff: f32,
+}
+
+impl ParamSmoother {
+    /// Creates a smoother resting at the given value.
+    pub(crate) fn new(value: f32) -> Self {
+        let mut smoother = Self::default();
+        smoother.reset(value);
+        smoother
+    }
+
+    /// Returns the value the smoother is ramping towards.
+    pub(crate) fn target(&self) -> f32 {
+        self.target
+    }
+
+    /// Returns whether the smoother has not yet reached its target.
+    pub(crate) fn is_active(&self) -> bool {
+        self.current != self.target
+    }
+
+    /// Jumps to the given value immediately.
+    pub(crate) fn reset(&mut self, value: f32) {
+        self.current = value;
+        self.target = value;
+        self.remaining = 0;
+    }
+
+    /// Starts a ramp from the current value to `target` over `ramp_samples` samples.
+    pub(crate) fn set_target(&mut self, target: f32, ramp_samples: u32, mode: SmoothingMode) {
+        if ramp_samples == 0 {
+            self.reset(target);
+            return;
+        }
+
+        self.target = target;
+        self.mode = mode;
+        match mode {
+            SmoothingMode::Linear => {
+                self.step = (target - self.current) / ramp_samples as f32;
+                self.remaining = ramp_samples;
+            }
+            SmoothingMode::Exponential => {
+                self.coeff = 0.01f32.powf(1.0 / ramp_samples as f32);
+            }
+        }
+    }
+
+    /// Appends the smoother's state to a snapshot.
+    pub(crate) fn write_state(&self, w: &mut SnapshotWriter) {
+        w.f32(self.current);
+        w.f32(self.target);
+        w.u8(self.mode as u8);
+        w.f32(self.step);
+        w.u32(self.remaining);
+        w.f32(self.coeff);
+    }
+
+    /// Reads a smoother's state written by [write_state](Self::write_state).
+    pub(crate) fn read_state(r: &mut SnapshotReader) -> Result<Self, Error> {
+        Ok(Self {
+            current: r.f32_in(0.0, 1.0)?,
+            target: r.f32_in(0.0, 1.0)?,
+            mode: match r.u8()? {
+                0 => SmoothingMode::Linear,
+                1 => SmoothingMode::Exponential,
+                _ => return Err(Error::InvalidSnapshot),
+            },
+            step: r.f32_in(-1.0, 1.0)?,
+            remaining: r.u32()?,
+            coeff: r.f32_in(0.0, 1.0)?,
+        })
+    }
+
+    /// Advances the ramp by the given number of samples and returns the new value.
+    pub(crate) fn advance(&mut self, samples: u32) -> f32 {
+        match self.mode {
+            SmoothingMode::Linear => {
+                let n = samples.min(self.remaining);
+                self.remaining -= n;
+                self.current = if self.remaining == 0 {
+                    self.target
+                } else {
+                    self.current + self.step * n as f32
+                };
+            }
+            SmoothingMode::Exponential => {
+                let distance = (self.current - self.target) * self.coeff.powi(samples as i32);
+                self.current = if distance.abs() < SETTLE_THRESHOLD {
+                    self.target
+                } else {
+                    self.target + distance
+                };
+            }
+        }
+        self.current
+    }
+}
@@ -0,0 +1,184 @@
+use crate::{Error, Sample};
+use std::fmt;
+
+/// Magic bytes at the start of every snapshot.
+const MAGIC: &[u8; 4] = b"CSRS";
+/// Version of the snapshot layout, increased whenever it changes.
+const VERSION: u32 = 5;
+
+/// The complete internal state of a [ReverbController](crate::ReverbController),
+/// including delay and allpass buffers, filter states, modulation phases and parameters.
+/// See [ReverbController::snapshot](crate::ReverbController::snapshot).
+///
+/// The snapshot can be stored as bytes and loaded again later,
+/// but only by the same version of this crate on the same platform.
+#[derive(Clone, PartialEq)]
+pub struct ReverbSnapshot {
+    bytes: Vec<u8>,
+}
+
+impl ReverbSnapshot {
+    /// Wraps bytes previously obtained from [as_bytes](Self::as_bytes)
+    /// or [into_bytes](Self::into_bytes).
+    /// Only the header is checked here, the contents are validated when restoring.
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, Error> {
+        SnapshotReader::new(&bytes)?;
+        Ok(Self { bytes })
+    }
+
+    /// Returns the serialized snapshot.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Returns the serialized snapshot.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+impl fmt::Debug for ReverbSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReverbSnapshot")
+            .field("len", &self.bytes.len())
+            .finish()
+    }
+}
+
+/// Builds a [ReverbSnapshot]. All values are stored little-endian.
+pub(crate) struct SnapshotWriter {
+    bytes: Vec<u8>,
+}
+
+impl SnapshotWriter {
+    pub(crate) fn new() -> Self {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        Self { bytes }
+    }
+
+    pub(crate) fn u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    pub(crate) fn u32(&mut self, value: u32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn f32(&mut self, value: f32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn f64(&mut self, value: f64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Appends an `f32` or `f64` sample in its own precision.
+    pub(crate) fn sample<S: Sample>(&mut self, value: S) {
+        if size_of::<S>() == size_of::<f32>() {
+            self.f32(value.to_f32());
+        } else {
+            self.f64(value.to_f64());
+        }
+    }
+
+    /// Appends a length-prefixed block of `len` zeroed bytes and returns it for filling in.
+    pub(crate) fn block(&mut self, len: usize) -> &mut [u8] {
+        self.bytes.extend_from_slice(&(len as u64).to_le_bytes());
+        let start = self.bytes.len();
+        self.bytes.resize(start + len, 0);
+        &mut self.bytes[start..]
+    }
+
+    pub(crate) fn finish(self) -> ReverbSnapshot {
+        ReverbSnapshot { bytes: self.bytes }
+    }
+}
+
+/// Reads the values written by a [SnapshotWriter] in the same order.
+/// Running out of data is reported as [Error::InvalidSnapshot].
+pub(crate) struct SnapshotReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> SnapshotReader<'a> {
+    /// Checks the header and positions the reader after it.
+    pub(crate) fn new(bytes: &'a [u8]) -> Result<Self, Error> {
+        let mut reader = Self { bytes };
+        if reader.take(MAGIC.len())? != MAGIC || reader.u32()? != VERSION {
+            return Err(Error::InvalidSnapshot);
+        }
+        Ok(reader)
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.bytes.len() < len {
+            return Err(Error::InvalidSnapshot);
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    pub(crate) fn bool(&mut self) -> Result<bool, Error> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(Error::InvalidSnapshot),
+        }
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32, Error> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    pub(crate) fn f32(&mut self) -> Result<f32, Error> {
+        Ok(f32::from_le_bytes(self.array()?))
+    }
+
+    pub(crate) fn f64(&mut self) -> Result<f64, Error> {
+        Ok(f64::from_le_bytes(self.array()?))
+    }
+
+    /// Reads a sample written by [SnapshotWriter::sample].
+    pub(crate) fn sample<S: Sample>(&mut self) -> Result<S, Error> {
+        if size_of::<S>() == size_of::<f32>() {
+            Ok(S::from_f32(self.f32()?))
+        } else {
+            Ok(S::from_f64(self.f64()?))
+        }
+    }
+
+    /// Reads a finite value in range `min..=max`.
+    pub(crate) fn f32_in(&mut self, min: f32, max: f32) -> Result<f32, Error> {
+        let value = self.f32()?;
+        if value >= min && value <= max {
+            Ok(value)
+        } else {
+            Err(Error::InvalidSnapshot)
+        }
+    }
+
+    /// Reads a block appended with [SnapshotWriter::block].
+    pub(crate) fn block(&mut self) -> Result<&'a [u8], Error> {
+        let len = u64::from_le_bytes(self.array()?);
+        self.take(usize::try_from(len).map_err(|_| Error::InvalidSnapshot)?)
+    }
+
+    /// Checks that all data has been read.
+    pub(crate) fn finish(self) -> Result<(), Error> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::InvalidSnapshot)
+        }
+    }
+}
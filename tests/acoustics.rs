@@ -0,0 +1,116 @@
+use cloudseedcore_rs::{
+    schroeder_curve, AcousticAnalysis, AcousticMetrics, OctaveBand, Program, StereoBuffer,
+    DARK_PLATE, OCTAVE_BANDS,
+};
+
+mod common;
+use common::noise;
+
+/// Noise decaying exponentially by 60 dB per `rt60` seconds, like an ideal diffuse room.
+fn exponential_decay(rt60: f64, seconds: f64) -> StereoBuffer {
+    let sample_rate = 48000.0;
+    let len = (seconds * sample_rate) as usize;
+    let envelope = |i: usize| 10f64.powf(-3.0 * i as f64 / sample_rate / rt60) as f32;
+    StereoBuffer {
+        sample_rate: sample_rate as f32,
+        left: noise(len, 1).iter().enumerate().map(|(i, x)| x * envelope(i)).collect(),
+        right: noise(len, 2).iter().enumerate().map(|(i, x)| x * envelope(i)).collect(),
+    }
+}
+
+fn short_plate() -> Program {
+    Program {
+        late_line_decay: 0.3,
+        ..DARK_PLATE
+    }
+}
+
+#[test]
+fn measures_exponential_decay() {
+    let rt60 = 1.0;
+    let m = AcousticMetrics::from_impulse_response(&exponential_decay(rt60, 2.0));
+    for time in [m.rt60, m.t20, m.t30, m.edt] {
+        let time = time.unwrap() as f64;
+        assert!((time - rt60).abs() < 0.03, "{m:?}");
+    }
+    assert_eq!(m.rt60, m.t30);
+
+    // the energy decays as exp(-k t)
+    let k = 6.0 * 10f64.ln() / rt60;
+    let d50 = 1.0 - (-k * 0.05).exp();
+    let c80 = 10.0 * ((1.0 - (-k * 0.08).exp()) / (-k * 0.08).exp()).log10();
+    assert!((m.d50 as f64 - d50).abs() < 0.02, "{m:?} {d50}");
+    assert!((m.c50 as f64 - 10.0 * (d50 / (1.0 - d50)).log10()).abs() < 0.3, "{m:?}");
+    assert!((m.c80 as f64 - c80).abs() < 0.3, "{m:?} {c80}");
+    assert!((m.centre_time as f64 - 1.0 / k).abs() < 0.005, "{m:?}");
+}
+
+#[test]
+fn decay_times_need_enough_dynamic_range() {
+    let mut impulse = StereoBuffer::new(48000.0, 4800);
+    impulse.left[10] = 1.0;
+    let m = AcousticMetrics::from_impulse_response(&impulse);
+    assert_eq!((m.rt60, m.t20, m.t30, m.edt), (None, None, None, None));
+    assert_eq!((m.d50, m.centre_time), (1.0, 0.0));
+
+    let silent = AcousticMetrics::from_impulse_response(&StereoBuffer::new(48000.0, 4800));
+    assert_eq!(silent.rt60, None);
+    assert!(silent.d50.is_nan());
+}
+
+#[test]
+fn schroeder_curve_decays_monotonically() {
+    let curve = schroeder_curve(&exponential_decay(0.5, 1.0));
+    assert_eq!(curve[0], 0.0);
+    assert!(curve.windows(2).all(|w| w[1] <= w[0]));
+    // -60 dB after about half a second
+    assert!((curve[24000] + 60.0).abs() < 3.0, "{}", curve[24000]);
+}
+
+#[test]
+fn octave_filter_separates_bands() {
+    let sine = |freq: f32| {
+        let len = 48000;
+        let signal: Vec<f32> = (0..len)
+            .map(|i| (2.0 * std::f32::consts::PI * freq * i as f32 / 48000.0).sin())
+            .collect();
+        StereoBuffer {
+            sample_rate: 48000.0,
+            left: signal.clone(),
+            right: signal,
+        }
+    };
+    let level = |band: OctaveBand, freq: f32| {
+        let filtered = band.filter(&sine(freq));
+        let peak = filtered.left[24000..].iter().fold(0.0f32, |m, x| m.max(x.abs()));
+        20.0 * peak.log10()
+    };
+    let band = OctaveBand { centre: 1000.0 };
+    assert!(level(band, 1000.0).abs() < 1.0);
+    assert!((level(band, band.upper_edge()) + 3.0).abs() < 0.5);
+    assert!((level(band, band.lower_edge()) + 3.0).abs() < 0.5);
+    assert!(level(band, 4000.0) < -30.0);
+    assert!(level(band, 250.0) < -30.0);
+}
+
+#[test]
+fn dark_plate_decays_faster_at_high_frequencies() {
+    let analysis = AcousticAnalysis::from_program(&short_plate(), 48000.0);
+    assert_eq!(analysis.octave_bands.len(), OCTAVE_BANDS.len());
+
+    let rt60 = |centre| analysis.band(centre).unwrap().rt60.unwrap();
+    assert!(rt60(8000.0) < rt60(500.0) * 0.75, "{analysis:?}");
+
+    // the dry signal is left out of the analysis
+    assert!(analysis.broadband.d50 < 0.5, "{analysis:?}");
+}
+
+#[test]
+fn longer_decay_raises_reverberation_time() {
+    let short = AcousticAnalysis::from_program(&short_plate(), 48000.0);
+    let long = AcousticAnalysis::from_program(&Program { late_line_decay: 0.5, ..DARK_PLATE }, 48000.0);
+    let (short, long) = (short.broadband, long.broadband);
+    assert!(long.rt60.unwrap() > short.rt60.unwrap() * 1.5, "{short:?} {long:?}");
+    assert!(long.centre_time > short.centre_time);
+    assert!(long.c80 < short.c80);
+}
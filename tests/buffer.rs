@@ -0,0 +1,83 @@
+use cloudseedcore_rs::{Interleaved, ReverbController, Sample, DARK_PLATE};
+
+mod common;
+use common::noise;
+
+const BLOCK: usize = 256;
+
+fn reverb() -> ReverbController {
+    let mut r = ReverbController::with_seed(48000.0, BLOCK as u32, 1);
+    r.set_program(&DARK_PLATE);
+    r
+}
+
+/// Renders the planar reference output with `process_any_len`.
+fn reference(left: &[f32], right: &[f32]) -> (Vec<f32>, Vec<f32>) {
+    let mut out_l = vec![0.0; left.len()];
+    let mut out_r = vec![0.0; left.len()];
+    reverb().process_any_len(left, right, &mut out_l, &mut out_r);
+    (out_l, out_r)
+}
+
+#[test]
+fn interleaved_matches_planar() {
+    let (left, right) = (noise(BLOCK * 10 + 7, 1), noise(BLOCK * 10 + 7, 2));
+    let (ref_l, ref_r) = reference(&left, &right);
+
+    let input: Vec<f32> = left.iter().zip(&right).flat_map(|(&l, &r)| [l, r]).collect();
+    let mut output = vec![0.0; input.len()];
+    reverb().process_interleaved(&input, &mut output);
+
+    let expected: Vec<f32> = ref_l.iter().zip(&ref_r).flat_map(|(&l, &r)| [l, r]).collect();
+    assert!(ref_l.iter().any(|&x| x != 0.0));
+    assert!(output == expected);
+}
+
+#[test]
+fn in_place_matches_planar() {
+    let (mut left, mut right) = (noise(BLOCK * 10 + 7, 3), noise(BLOCK * 10 + 7, 4));
+    let (ref_l, ref_r) = reference(&left, &right);
+
+    reverb().process_in_place(&mut left, &mut right);
+    assert!(left == ref_l && right == ref_r);
+}
+
+#[test]
+fn channel_slices_of_other_formats_match_planar() {
+    let (left, right) = (noise(BLOCK * 3, 5), noise(BLOCK * 3, 6));
+    let (ref_l, ref_r) = reference(&left, &right);
+
+    let input: [Vec<f64>; 2] = [&left, &right].map(|c| c.iter().map(|&x| x as f64).collect());
+    let channels: &[&[f64]] = &[&input[0], &input[1]];
+    let mut out_l = vec![0.0f64; left.len()];
+    let mut out_r = vec![0.0f64; left.len()];
+    reverb().process_buffers(channels, &mut [&mut out_l[..], &mut out_r[..]]);
+
+    assert!(out_l.iter().zip(&ref_l).all(|(&a, &b)| a == b as f64));
+    assert!(out_r.iter().zip(&ref_r).all(|(&a, &b)| a == b as f64));
+}
+
+#[test]
+fn integer_samples_are_converted() {
+    assert_eq!(i16::MIN.to_f32(), -1.0);
+    assert_eq!(i16::from_f32(0.5), 16384);
+    assert_eq!(i16::from_f32(2.0), i16::MAX);
+    assert_eq!(i16::from_f32(-2.0), i16::MIN);
+    assert_eq!(i32::MIN.to_f32(), -1.0);
+    assert_eq!(i32::from_f32(0.25), 1 << 29);
+    assert_eq!(i32::from_f32(1.0), i32::MAX);
+
+    let input = vec![i16::MAX / 2; BLOCK * 4];
+    let mut output = vec![0i32; BLOCK * 4];
+    let mut r = reverb();
+    r.process_buffers(&Interleaved(&input[..]), &mut Interleaved(&mut output[..]));
+    assert!(output.iter().any(|&x| x != 0));
+}
+
+#[test]
+#[should_panic]
+fn mismatched_lengths_panic() {
+    let input = vec![0.0f32; 100];
+    let mut output = vec![0.0f32; 102];
+    reverb().process_interleaved(&input, &mut output);
+}
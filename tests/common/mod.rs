@@ -0,0 +1,35 @@
+//! Helpers shared by the integration tests.
+// each test binary compiles this module and only uses some of the helpers
+#![allow(dead_code)]
+
+use cloudseedcore_rs::{DARK_PLATE, EngineSample, Program, ReverbController};
+
+/// A program that passes the input through unchanged.
+pub fn dry_only() -> Program {
+    let mut p = DARK_PLATE;
+    p.input_mix = 0.0;
+    p.dry_out = 1.0;
+    p.early_out = 0.0;
+    p.late_out = 0.0;
+    p
+}
+
+/// Generates deterministic white noise in range -1..1.
+pub fn noise(len: usize, seed: u32) -> Vec<f32> {
+    let mut state = seed;
+    (0..len)
+        .map(|_| {
+            state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+            (state >> 8) as f32 / (1 << 23) as f32 - 1.0
+        })
+        .collect()
+}
+
+/// Processes the input on both channels and returns the left output followed by the right.
+pub fn run<S: EngineSample>(r: &mut ReverbController<S>, input: &[S]) -> Vec<S> {
+    let mut out_l = vec![S::default(); input.len()];
+    let mut out_r = vec![S::default(); input.len()];
+    r.process_any_len(input, input, &mut out_l, &mut out_r);
+    out_l.extend(out_r);
+    out_l
+}
@@ -60,7 +60,7 @@ fn test_roundtrip_all_params() {
 
             // parse back
             let parsed = parse_parameter_text(param, &formatted)
-                .expect(&format!("could not parse back `{}`", formatted));
+                .unwrap_or_else(|| panic!("could not parse back `{}`", formatted));
 
             // format again
             let formatted_again = format_parameter_value(param, parsed);
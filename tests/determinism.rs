@@ -0,0 +1,54 @@
+use cloudseedcore_rs::{ReverbController, DARK_PLATE};
+
+mod common;
+use common::noise;
+
+const BLOCK: usize = 256;
+
+/// Renders a noise burst followed by silence through the reverb.
+fn render(r: &mut ReverbController) -> Vec<f32> {
+    r.set_program(&DARK_PLATE);
+    let burst = noise(BLOCK * 4, 1);
+    let silence = vec![0.0; BLOCK * 16];
+    let input: Vec<f32> = burst.into_iter().chain(silence).collect();
+
+    let mut output = Vec::new();
+    let mut out_l = [0.0f32; BLOCK];
+    let mut out_r = [0.0f32; BLOCK];
+    for block in input.chunks(BLOCK) {
+        r.process(block, block, &mut out_l, &mut out_r, BLOCK as u32);
+        output.extend_from_slice(&out_l);
+        output.extend_from_slice(&out_r);
+    }
+    output
+}
+
+#[test]
+fn same_seed_renders_identically() {
+    let a = render(&mut ReverbController::with_seed(48000.0, BLOCK as u32, 7));
+    let b = render(&mut ReverbController::with_seed(48000.0, BLOCK as u32, 7));
+    assert!(a.iter().any(|&x| x != 0.0));
+    assert!(a == b);
+}
+
+#[test]
+fn different_seeds_render_differently() {
+    let a = render(&mut ReverbController::with_seed(48000.0, BLOCK as u32, 7));
+    let b = render(&mut ReverbController::with_seed(48000.0, BLOCK as u32, 8));
+    assert!(a != b);
+}
+
+#[test]
+fn seed_reproduces_randomly_seeded_instance() {
+    let mut r = ReverbController::new(48000.0, BLOCK as u32);
+    let mut reproduced = ReverbController::with_seed(48000.0, BLOCK as u32, r.seed());
+    assert!(render(&mut r) == render(&mut reproduced));
+}
+
+#[test]
+fn clone_renders_like_original() {
+    let r = ReverbController::try_with_seed(48000.0, BLOCK as u32, 42).unwrap();
+    let mut cloned = r.clone();
+    assert_eq!(cloned.seed(), 42);
+    assert!(render(&mut r.clone()) == render(&mut cloned));
+}
@@ -0,0 +1,115 @@
+use cloudseedcore_rs::{Error, ReverbController, MAX_INTERNAL_BLOCK_SIZE, MAX_SAMPLE_RATE};
+
+#[test]
+fn try_new_accepts_valid_arguments() {
+    let r = ReverbController::try_new(48000.0, 512).expect("valid arguments");
+    assert_eq!(r.max_block_size(), 512);
+}
+
+#[test]
+fn try_new_rejects_invalid_sample_rates() {
+    for rate in [0.0, -48000.0, f32::NAN, f32::INFINITY] {
+        assert!(matches!(
+            ReverbController::try_new(rate, 512),
+            Err(Error::InvalidSampleRate(_))
+        ));
+    }
+
+    for rate in [100.0, 44100.5, MAX_SAMPLE_RATE * 2.0] {
+        assert!(matches!(
+            ReverbController::try_new(rate, 512),
+            Err(Error::UnsupportedSampleRate(_))
+        ));
+    }
+}
+
+#[test]
+fn try_new_rejects_invalid_block_sizes() {
+    let max = ReverbController::max_internal_block_size();
+    for block_size in [0, max + 1] {
+        assert_eq!(
+            ReverbController::try_new(48000.0, block_size).err(),
+            Some(Error::InvalidBlockSize { block_size, max })
+        );
+    }
+}
+
+#[test]
+#[should_panic]
+fn new_rejects_block_size_above_internal_maximum() {
+    ReverbController::new(48000.0, MAX_INTERNAL_BLOCK_SIZE + 1);
+}
+
+#[test]
+fn new_accepts_internal_maximum_block_size() {
+    let mut r = ReverbController::new(48000.0, MAX_INTERNAL_BLOCK_SIZE);
+    assert_eq!(ReverbController::max_internal_block_size(), MAX_INTERNAL_BLOCK_SIZE);
+
+    let n = MAX_INTERNAL_BLOCK_SIZE as usize;
+    let input = vec![0.0f32; n];
+    let mut out_l = vec![0.0f32; n];
+    let mut out_r = vec![0.0f32; n];
+    r.process(&input, &input, &mut out_l, &mut out_r, n as u32);
+}
+
+#[test]
+fn try_process_rejects_invalid_buffers() {
+    let mut r = ReverbController::try_new(48000.0, 64).unwrap();
+    let input = vec![0.0f32; 128];
+    let mut out_l = vec![0.0f32; 128];
+    let mut out_r = vec![0.0f32; 32];
+
+    assert_eq!(
+        r.try_process(&input, &input, &mut out_l, &mut out_r, 128),
+        Err(Error::BlockTooLarge {
+            num_samples: 128,
+            max_block_size: 64
+        })
+    );
+    assert_eq!(
+        r.try_process(&input, &input, &mut out_l, &mut out_r, 64),
+        Err(Error::BufferTooShort {
+            len: 32,
+            num_samples: 64
+        })
+    );
+    assert_eq!(
+        r.try_process(&input, &input, &mut out_l, &mut out_r, 32),
+        Ok(())
+    );
+}
+
+#[test]
+fn try_set_sample_rate_validates() {
+    let mut r = ReverbController::try_new(48000.0, 64).unwrap();
+    assert_eq!(r.try_set_sample_rate(96000.0), Ok(()));
+    assert_eq!(
+        r.try_set_sample_rate(-1.0),
+        Err(Error::InvalidSampleRate(-1.0))
+    );
+    assert!(matches!(
+        r.try_set_sample_rate(MAX_SAMPLE_RATE * 2.0),
+        Err(Error::UnsupportedSampleRate(_))
+    ));
+    // rejected rates leave the reverb unchanged
+    assert_eq!(r.get_sample_rate(), 96000.0);
+}
+
+#[test]
+#[should_panic(expected = "sample_rate")]
+fn new_rejects_invalid_sample_rate() {
+    ReverbController::new(0.0, 512);
+}
+
+#[test]
+#[should_panic(expected = "sample_rate")]
+fn new_rejects_unsupported_sample_rate() {
+    ReverbController::new(44100.5, 512);
+}
+
+#[test]
+#[should_panic(expected = "sample_rate")]
+fn set_sample_rate_rejects_unsupported_sample_rate() {
+    let mut r = ReverbController::new(48000.0, 512);
+    r.set_sample_rate(f32::NAN);
+}
@@ -0,0 +1,114 @@
+use cloudseedcore_rs::{
+    fit_program, render_impulse_response, FitOptions, ParamId, Program, StereoBuffer, DARK_PLATE,
+};
+
+const SAMPLE_RATE: f32 = 16000.0;
+
+/// Renders a one second impulse response, preceded by silence like a measured response.
+fn target(program: &Program) -> StereoBuffer {
+    let ir = render_impulse_response(program, SAMPLE_RATE, SAMPLE_RATE as usize);
+    let delay = vec![0.0; 400];
+    StereoBuffer {
+        sample_rate: SAMPLE_RATE,
+        left: [delay.clone(), ir.left].concat(),
+        right: [delay, ir.right].concat(),
+    }
+}
+
+fn options(parameters: &[ParamId], max_evaluations: usize) -> FitOptions {
+    FitOptions {
+        parameters: parameters.to_vec(),
+        max_evaluations,
+        ..FitOptions::default()
+    }
+}
+
+#[test]
+fn fit_recovers_decay_of_rendered_program() {
+    let program = Program {
+        late_line_decay: 0.45,
+        ..DARK_PLATE
+    };
+    let fit = fit_program(&target(&program), &options(&[ParamId::LateLineDecay], 30));
+    assert!(fit.evaluations <= 30);
+    assert!(
+        (fit.program.late_line_decay - 0.45).abs() < 0.03,
+        "{}",
+        fit.program.late_line_decay
+    );
+    assert!(fit.decay_error_db < 1.0, "{fit:?}");
+}
+
+#[test]
+fn fit_improves_on_initial_program() {
+    let program = Program {
+        late_line_decay: 0.4,
+        late_line_size: 0.3,
+        late_diffuse_feedback: 0.5,
+        eq_high_shelf_enabled: false,
+        ..DARK_PLATE
+    };
+    let target = target(&program);
+    let parameters = [
+        ParamId::LateLineDecay,
+        ParamId::LateLineSize,
+        ParamId::LateDiffuseFeedback,
+    ];
+
+    // a single evaluation only measures the initial program
+    let initial = fit_program(&target, &options(&parameters, 1));
+    assert_eq!(initial.evaluations, 1);
+    assert_eq!(initial.program, DARK_PLATE);
+
+    let fit = fit_program(&target, &options(&parameters, 40));
+    assert!(fit.distance < initial.distance * 0.5, "{} {}", fit.distance, initial.distance);
+    assert!(fit.decay_error_db < initial.decay_error_db);
+}
+
+#[test]
+fn fit_keeps_other_parameters() {
+    let program = Program {
+        late_out: 0.9,
+        late_line_decay: 0.4,
+        ..DARK_PLATE
+    };
+    let fit = fit_program(&target(&program), &options(&[ParamId::LateLineDecay], 10));
+    let expected = Program {
+        late_line_decay: fit.program.late_line_decay,
+        ..DARK_PLATE
+    };
+    assert_eq!(fit.program, expected);
+    for value in fit.program.to_array() {
+        assert!((0.0..=1.0).contains(&value));
+    }
+}
+
+#[test]
+fn fit_report_compares_octave_bands() {
+    let fit = fit_program(&target(&DARK_PLATE), &options(&[ParamId::LateLineDecay], 3));
+    // bands above 5.6 kHz don't fit below the Nyquist frequency
+    assert_eq!(fit.target.octave_bands.len(), 7);
+    assert_eq!(fit.fitted.octave_bands.len(), 7);
+
+    // the target is compared from its onset, so the initial program matches it exactly
+    assert_eq!(fit.program, DARK_PLATE);
+    assert_eq!(fit.distance, 0.0);
+    assert_eq!(fit.target, fit.fitted);
+}
+
+#[test]
+#[should_panic(expected = "empty")]
+fn fit_rejects_empty_target() {
+    let target = StereoBuffer::new(SAMPLE_RATE, 0);
+    fit_program(&target, &options(&[ParamId::LateLineDecay], 3));
+}
+
+#[test]
+#[should_panic(expected = "sample_rate")]
+fn fit_rejects_unsupported_sample_rate() {
+    let target = StereoBuffer {
+        sample_rate: 0.0,
+        ..target(&DARK_PLATE)
+    };
+    fit_program(&target, &options(&[ParamId::LateLineDecay], 3));
+}
@@ -0,0 +1,98 @@
+use cloudseedcore_rs::{
+    render_impulse_response, Program, ReverbController, StereoBuffer, DARK_PLATE,
+};
+
+const BLOCK: usize = 256;
+
+fn energy(ir: &StereoBuffer) -> f64 {
+    ir.left.iter().chain(&ir.right).map(|&x| (x as f64) * (x as f64)).sum()
+}
+
+#[test]
+fn matches_manual_impulse_loop() {
+    let ir = render_impulse_response(&DARK_PLATE, 48000.0, 48000);
+    assert_eq!(ir.len(), 48000);
+    assert_eq!(ir.sample_rate, 48000.0);
+    assert!(energy(&ir) > 1e-3);
+
+    // the response is rendered with a fixed seed
+    assert!(render_impulse_response(&DARK_PLATE, 48000.0, 48000) == ir);
+    let mut r = ReverbController::with_seed(48000.0, BLOCK as u32, 0);
+    r.set_program(&DARK_PLATE);
+    let mut input = vec![0.0f32; 48000];
+    input[0] = 1.0;
+    let mut manual = StereoBuffer::new(48000.0, 48000);
+    r.process_any_len(&input, &input, &mut manual.left, &mut manual.right);
+    assert!(manual == ir);
+}
+
+#[test]
+fn trims_inaudible_tail() {
+    let mut program = DARK_PLATE;
+    program.late_line_decay = 0.3;
+    let full = render_impulse_response(&program, 48000.0, 48000 * 10);
+    let trimmed = full.clone().trimmed(-60.0);
+    assert!(trimmed.len() < full.len());
+    assert!(trimmed.len() > 4800, "{}", trimmed.len());
+    assert_eq!(trimmed.left.len(), trimmed.right.len());
+    assert!(trimmed.left[..] == full.left[..trimmed.len()]);
+
+    // the removed part holds less than the threshold's share of the energy
+    let removed = energy(&full) - energy(&trimmed);
+    assert!(removed <= energy(&full) * 1e-6, "{removed}");
+
+    // a lower threshold keeps more of the tail
+    assert!(full.clone().trimmed(-90.0).len() > trimmed.len());
+}
+
+#[test]
+fn silent_response_trims_to_nothing() {
+    let mut program = DARK_PLATE;
+    program.dry_out = 0.0;
+    program.early_out = 0.0;
+    program.late_out = 0.0;
+    let ir = render_impulse_response(&program, 48000.0, 4800);
+    assert!(ir.trimmed(-60.0).is_empty());
+    assert!(render_impulse_response(&DARK_PLATE, 48000.0, 0).is_empty());
+}
+
+#[cfg(feature = "wav")]
+#[test]
+fn writes_wav_file() {
+    let ir = render_impulse_response(&DARK_PLATE, 48000.0, 4800);
+    let dir = std::path::PathBuf::from("target/reverb_wavs");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("impulse_response.wav");
+    ir.write_wav(&path).unwrap();
+
+    let mut reader = hound::WavReader::open(&path).unwrap();
+    assert_eq!(reader.spec().channels, 2);
+    assert_eq!(reader.spec().sample_rate, 48000);
+    let samples: Vec<f32> = reader.samples::<f32>().map(Result::unwrap).collect();
+    assert_eq!(samples.len(), ir.len() * 2);
+    assert_eq!(samples[200], ir.left[100]);
+    assert_eq!(samples[201], ir.right[100]);
+}
+
+#[test]
+#[should_panic(expected = "sample_rate")]
+fn rejects_zero_sample_rate() {
+    render_impulse_response(&DARK_PLATE, 0.0, 48000);
+}
+
+#[test]
+#[should_panic(expected = "sample_rate")]
+fn rejects_nan_sample_rate() {
+    render_impulse_response(&DARK_PLATE, f32::NAN, 48000);
+}
+
+#[test]
+#[should_panic(expected = "sample_rate")]
+fn analysis_of_muted_program_rejects_invalid_sample_rate() {
+    let muted = Program {
+        early_out: 0.0,
+        late_out: 0.0,
+        ..DARK_PLATE
+    };
+    muted.loudness_compensation(0.0);
+}
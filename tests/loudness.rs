@@ -0,0 +1,223 @@
+use cloudseedcore_rs::{MeterPoint, ParamId, Program, ReverbController, DARK_PLATE};
+
+mod common;
+use common::noise;
+
+const BLOCK: usize = 256;
+
+fn reverb() -> ReverbController {
+    let mut r = ReverbController::with_seed(48000.0, BLOCK as u32, 1);
+    r.set_program(&DARK_PLATE);
+    r
+}
+
+/// A short, bright program that is much quieter than [DARK_PLATE].
+fn quiet_program() -> Program {
+    Program {
+        dry_out: 0.0,
+        late_out: 0.3,
+        late_line_count: 0.0,
+        late_line_decay: 0.3,
+        eq_high_shelf_enabled: false,
+        ..DARK_PLATE
+    }
+}
+
+/// Returns the short-term loudness of the input and output after playing noise through the reverb.
+fn loudness(r: &mut ReverbController, seconds: usize) -> (f32, f32) {
+    r.set_metering(true);
+    let input = noise(48000 * seconds, 7);
+    let mut out_l = vec![0.0f32; input.len()];
+    let mut out_r = vec![0.0f32; input.len()];
+    r.process_any_len(&input, &input, &mut out_l, &mut out_r);
+    let meters = r.meters();
+    (
+        meters.read(MeterPoint::Input).short_term_lufs,
+        meters.read(MeterPoint::Output).short_term_lufs,
+    )
+}
+
+#[test]
+fn compensation_follows_output_levels() {
+    let program = Program {
+        late_out: 0.66,
+        ..DARK_PLATE
+    };
+    let quieter = Program {
+        late_out: 0.33,
+        ..program
+    };
+    let difference =
+        quieter.loudness_compensation(48000.0) - program.loudness_compensation(48000.0);
+    // LateOut is scaled linearly from -30 to 0 dB
+    assert!((difference - 9.9).abs() < 0.01, "{difference}");
+
+    // the dry signal doesn't count
+    let dry = Program {
+        dry_out: 0.2,
+        ..program
+    };
+    assert_eq!(
+        dry.loudness_compensation(48000.0),
+        program.loudness_compensation(48000.0)
+    );
+
+    let muted = Program {
+        early_out: 0.0,
+        late_out: 0.0,
+        ..program
+    };
+    assert_eq!(muted.loudness_compensation(48000.0), 0.0);
+}
+
+#[test]
+fn compensated_programs_are_as_loud_as_noise_played_into_them() {
+    let loud = Program {
+        dry_out: 0.0,
+        late_line_decay: 0.3,
+        ..DARK_PLATE
+    };
+    let mut uncompensated = Vec::new();
+    for program in [loud, quiet_program()] {
+        let mut r = reverb();
+        r.set_program(&program);
+        uncompensated.push(loudness(&mut r, 4).1);
+
+        r.reset();
+        r.set_auto_gain(true);
+        let (input, output) = loudness(&mut r, 4);
+        assert!((output - input).abs() < 1.0, "{input} {output}");
+    }
+    assert!(uncompensated[0] - uncompensated[1] > 5.0, "{uncompensated:?}");
+}
+
+#[test]
+fn auto_gain_tracks_program_loads_and_line_count() {
+    let mut r = reverb();
+    assert!(!r.is_auto_gain());
+    assert_eq!(r.auto_gain_db(), 0.0);
+
+    r.set_auto_gain(true);
+    assert!(r.is_auto_gain());
+    assert_eq!(r.auto_gain_db(), DARK_PLATE.loudness_compensation(48000.0));
+
+    r.set_program(&quiet_program());
+    assert_eq!(
+        r.auto_gain_db(),
+        quiet_program().loudness_compensation(48000.0)
+    );
+
+    r.set_parameter(ParamId::LateLineCount, 1.0);
+    let expected = r.get_program().loudness_compensation(48000.0);
+    assert_eq!(r.auto_gain_db(), expected);
+
+    // other parameters change the level as usual
+    r.set_parameter(ParamId::LateOut, 0.2);
+    assert_eq!(r.auto_gain_db(), expected);
+
+    // the line count doesn't undo the level trim
+    r.set_parameter(ParamId::LateLineCount, 0.5);
+    let loaded = Program {
+        late_out: quiet_program().late_out,
+        ..r.get_program()
+    };
+    assert_eq!(r.auto_gain_db(), loaded.loudness_compensation(48000.0));
+    assert_ne!(r.auto_gain_db(), r.get_program().loudness_compensation(48000.0));
+
+    r.set_auto_gain(false);
+    assert_eq!(r.auto_gain_db(), 0.0);
+}
+
+#[test]
+fn line_count_change_ramps_the_wet_gain() {
+    let len = 48000;
+    let input = noise(len, 3);
+    let render = |auto_gain: bool| {
+        let mut r = reverb();
+        r.set_parameter(ParamId::DryOut, 0.0);
+        r.set_auto_gain(auto_gain);
+        r.set_smoothing(0.1, Default::default());
+        let before = r.auto_gain_db();
+        let mut out_l = vec![0.0f32; len];
+        let mut out_r = vec![0.0f32; len];
+        let (first, second) = input.split_at(len / 2);
+        let (first_l, second_l) = out_l.split_at_mut(len / 2);
+        let (first_r, second_r) = out_r.split_at_mut(len / 2);
+        r.process_any_len(first, first, first_l, first_r);
+        r.set_parameter(ParamId::LateLineCount, 0.0);
+        r.process_any_len(second, second, second_l, second_r);
+        (out_l, before, r.auto_gain_db())
+    };
+    let (plain, _, _) = render(false);
+    let (compensated, before, after) = render(true);
+    assert!((after - before).abs() > 0.5, "{before} {after}");
+
+    // the gain applied over a few samples, relative to the uncompensated output
+    let gain_at = |start: usize| {
+        let range = start..start + 64;
+        let cross: f32 = range.clone().map(|i| compensated[i] * plain[i]).sum();
+        let energy: f32 = range.map(|i| plain[i] * plain[i]).sum();
+        cross / energy
+    };
+    let db = |gain: f32| 20.0 * gain.log10();
+    assert!((db(gain_at(len / 2)) - before).abs() < 0.05, "{}", db(gain_at(len / 2)));
+    assert!((db(gain_at(len - 64)) - after).abs() < 0.01);
+    let halfway = db(gain_at(len / 2 + 2400 - 32));
+    assert!(
+        halfway > before.min(after) + 0.1 && halfway < before.max(after) - 0.1,
+        "{halfway}"
+    );
+}
+
+#[test]
+fn auto_gain_applies_changes_from_handle() {
+    let mut r = reverb();
+    r.set_auto_gain(true);
+    let params = r.params();
+
+    std::thread::spawn(move || params.set_program(&quiet_program()))
+        .join()
+        .unwrap();
+    let input = vec![0.0f32; BLOCK];
+    let mut out_l = vec![0.0f32; BLOCK];
+    let mut out_r = vec![0.0f32; BLOCK];
+    r.process(&input, &input, &mut out_l, &mut out_r, BLOCK as u32);
+    assert_eq!(
+        r.auto_gain_db(),
+        quiet_program().loudness_compensation(48000.0)
+    );
+
+    r.params().set_parameter(ParamId::LateLineCount, 1.0);
+    r.process(&input, &input, &mut out_l, &mut out_r, BLOCK as u32);
+    assert_eq!(
+        r.auto_gain_db(),
+        r.get_program().loudness_compensation(48000.0)
+    );
+}
+
+#[test]
+fn auto_gain_scales_only_the_wet_signal() {
+    let len = 48000;
+    let mut input = vec![0.0f32; len];
+    input[0] = 1.0;
+    let render = |auto_gain: bool| {
+        let mut r = reverb();
+        r.set_auto_gain(auto_gain);
+        let mut out_l = vec![0.0f32; len];
+        let mut out_r = vec![0.0f32; len];
+        r.process_any_len(&input, &input, &mut out_l, &mut out_r);
+        (out_l, r.auto_gain_db())
+    };
+    let (plain, _) = render(false);
+    let (compensated, db) = render(true);
+    let gain = 10f32.powf(db / 20.0);
+    assert!((gain - 1.0).abs() > 0.1, "{db}");
+
+    // the impulse only reaches the output through the dry path at first
+    assert_eq!(compensated[0], plain[0]);
+    let peak = plain[1..].iter().fold(0.0f32, |m, x| m.max(x.abs()));
+    assert!(peak > 0.0);
+    for (c, p) in compensated[1..].iter().zip(&plain[1..]) {
+        assert!((c - p * gain).abs() < peak * 1e-4, "{c} {p}");
+    }
+}
@@ -0,0 +1,147 @@
+use cloudseedcore_rs::{
+    render_impulse_response, AcousticMetrics, Excitation, Mls, OctaveBand, Program,
+    ReverbController, SineSweep, SpectralAnalysis, StereoBuffer, DARK_PLATE,
+};
+
+mod common;
+use common::noise;
+
+const BLOCK: usize = 256;
+
+/// Convolves the excitation signal with a known impulse response,
+/// simulating a recording of a linear system.
+fn record(excitation: &impl Excitation, ir: &[f32], ir_len: usize) -> StereoBuffer {
+    let signal = excitation.signal(48000.0);
+    let len = excitation.recording_len(48000.0, ir_len);
+    let mut recording = StereoBuffer::new(48000.0, len);
+    for (i, &x) in signal.iter().enumerate() {
+        for (j, &h) in ir.iter().enumerate() {
+            if let Some(y) = recording.left.get_mut(i + j) {
+                *y += x * h;
+            }
+        }
+    }
+    recording.right = recording.left.iter().map(|x| -x).collect();
+    recording
+}
+
+fn energy(signal: &[f32]) -> f64 {
+    signal.iter().map(|&x| (x as f64) * (x as f64)).sum()
+}
+
+fn short_plate() -> Program {
+    Program {
+        late_line_decay: 0.2,
+        dry_out: 0.0,
+        ..DARK_PLATE
+    }
+}
+
+fn reverb(program: &Program) -> ReverbController {
+    let mut r = ReverbController::with_seed(48000.0, BLOCK as u32, 1);
+    r.set_program(program);
+    r
+}
+
+/// Asserts that the decay times in the main octave bands match within `tolerance`.
+fn assert_decay_matches(measured: &StereoBuffer, expected: &StereoBuffer, tolerance: f32) {
+    for centre in [250.0, 1000.0, 4000.0] {
+        let band = OctaveBand { centre };
+        let rt60 = |ir| AcousticMetrics::from_impulse_response(&band.filter(ir)).rt60.unwrap();
+        let ratio = rt60(measured) / rt60(expected);
+        assert!((ratio - 1.0).abs() < tolerance, "{centre} Hz: {ratio}");
+    }
+}
+
+#[test]
+fn mls_has_two_valued_autocorrelation() {
+    for order in 2..=12 {
+        let sequence = Mls { order, repetitions: 2 }.sequence();
+        let len = sequence.len();
+        assert_eq!(len, (1 << order) - 1);
+        for shift in 0..len {
+            let sum: f32 = (0..len).map(|i| sequence[i] * sequence[(i + shift) % len]).sum();
+            assert_eq!(sum, if shift == 0 { len as f32 } else { -1.0 }, "order {order}");
+        }
+    }
+    for order in 13..=20 {
+        let sequence = Mls { order, repetitions: 2 }.sequence();
+        assert_eq!(sequence.iter().sum::<f32>(), 1.0, "order {order}");
+    }
+}
+
+#[test]
+fn sweep_recovers_delayed_impulse() {
+    let sweep = SineSweep {
+        start_freq: 20.0,
+        end_freq: 20000.0,
+        duration: 1.0,
+    };
+    let mut system = vec![0.0f32; 101];
+    system[100] = 0.5;
+    let ir = sweep.deconvolve(&record(&sweep, &system, 4800), 4800);
+    assert_eq!(ir.len(), 4800);
+
+    // the response is band-limited to the sweep's range, which lowers the peak
+    let peak = ir.left.iter().map(|x| x.abs()).enumerate().max_by(|a, b| a.1.total_cmp(&b.1));
+    assert_eq!(peak.unwrap().0, 100);
+    assert!(ir.left[100] > 0.35 && ir.right[100] < -0.35);
+
+    // 0.5 in both channels is -3 dB within the sweep's range
+    let spectral = SpectralAnalysis::from_impulse_response(&ir, 1024);
+    for freq in [200.0, 1000.0, 10000.0] {
+        let db = spectral.magnitude_db[spectral.band(freq)];
+        assert!((db + 3.01).abs() < 0.2, "{freq} {db}");
+    }
+}
+
+#[test]
+fn mls_recovers_decaying_response() {
+    let mls = Mls {
+        order: 12,
+        repetitions: 3,
+    };
+    let system: Vec<f32> = noise(1000, 1)
+        .iter()
+        .enumerate()
+        .map(|(i, x)| x * (-(i as f32) / 200.0).exp())
+        .collect();
+    let ir = mls.deconvolve(&record(&mls, &system, 2000), 2000);
+
+    // the circular correlation leaves an offset of the system's sum divided by the period
+    let expected = system.iter().chain(&[0.0; 1000]);
+    let error: f64 = ir.left.iter().zip(expected).map(|(&a, &b)| ((a - b) as f64).powi(2)).sum();
+    assert!(error < energy(&system) * 1e-4, "{error}");
+}
+
+#[test]
+fn sweep_measures_modulated_reverb() {
+    let rendered = render_impulse_response(&short_plate(), 48000.0, 96000);
+    let sweep = SineSweep {
+        start_freq: 20.0,
+        end_freq: 20000.0,
+        duration: 2.0,
+    };
+    let measured = sweep.measure(&mut reverb(&short_plate()), 96000);
+    assert_decay_matches(&measured, &rendered, 0.05);
+}
+
+#[test]
+fn mls_measures_unmodulated_reverb() {
+    let program = Program {
+        early_diffuse_mod_amount: 0.0,
+        late_line_mod_amount: 0.0,
+        late_diffuse_mod_amount: 0.0,
+        ..short_plate()
+    };
+    let rendered = render_impulse_response(&program, 48000.0, 96000);
+    let mls = Mls {
+        order: 17,
+        repetitions: 2,
+    };
+    let measured = mls.measure(&mut reverb(&program), 96000);
+    assert_decay_matches(&measured, &rendered, 0.05);
+
+    let db = 10.0 * (energy(&measured.left) / energy(&rendered.left)).log10();
+    assert!(db.abs() < 0.1, "{db}");
+}
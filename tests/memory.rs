@@ -0,0 +1,70 @@
+use cloudseedcore_rs::{MAX_SAMPLE_RATE, ParamId, Program, ReverbController, DARK_PLATE};
+
+mod common;
+use common::{noise, run};
+
+const BLOCK: usize = 256;
+
+#[test]
+fn memory_scales_with_sample_rate() {
+    let mut r = ReverbController::new(48000.0, BLOCK as u32);
+    let at_48k = r.memory_usage();
+    // the buffers used to be sized for 192 kHz regardless of the rate, about 66 MB
+    assert!(at_48k < 20_000_000, "{at_48k}");
+
+    r.set_sample_rate(96000.0);
+    let at_96k = r.memory_usage();
+    assert!(at_96k > at_48k * 19 / 10 && at_96k < at_48k * 21 / 10, "{at_48k} {at_96k}");
+
+    r.set_sample_rate(48000.0);
+    assert_eq!(r.memory_usage(), at_48k);
+}
+
+#[test]
+fn crossfade_engine_counts_towards_memory() {
+    let mut r = ReverbController::new(48000.0, BLOCK as u32);
+    let single = r.memory_usage();
+    r.set_program_crossfade(&DARK_PLATE, 0.1);
+    assert!(r.memory_usage() > single * 2 - 100_000);
+}
+
+#[test]
+fn runs_at_maximum_sample_rate_with_longest_delays() {
+    let mut r = ReverbController::try_new(MAX_SAMPLE_RATE, BLOCK as u32).unwrap();
+    r.set_program(&Program::from_array([1.0; 45]));
+    r.set_parameter(ParamId::DryOut, 0.0);
+
+    // the signal only appears after the 500 ms predelay
+    let mut input = noise(MAX_SAMPLE_RATE as usize / 4, 1);
+    input.resize(MAX_SAMPLE_RATE as usize * 2, 0.0);
+    let output = run(&mut r, &input);
+    assert!(output.iter().all(|x| x.is_finite()));
+    assert!(output.iter().any(|&x| x != 0.0));
+}
+
+#[test]
+fn out_of_range_parameters_stay_within_buffers() {
+    let mut r = ReverbController::new(48000.0, BLOCK as u32);
+    r.set_program(&DARK_PLATE);
+    r.set_parameter(ParamId::LateLineSize, 100.0);
+    r.set_parameter(ParamId::TapPredelay, 100.0);
+    r.set_parameter(ParamId::EarlyDiffuseModAmount, f32::NAN);
+
+    let output = run(&mut r, &noise(BLOCK * 32, 2));
+    assert!(output.iter().all(|x| x.is_finite()));
+}
+
+#[test]
+fn restore_across_sample_rates() {
+    let mut r = ReverbController::with_seed(96000.0, BLOCK as u32, 1);
+    r.set_program(&DARK_PLATE);
+    run(&mut r, &noise(BLOCK * 8, 3));
+    let snapshot = r.snapshot();
+
+    let mut other = ReverbController::with_seed(44100.0, BLOCK as u32, 2);
+    other.restore(&snapshot).unwrap();
+    assert_eq!(other.memory_usage(), r.memory_usage());
+
+    let input = noise(BLOCK * 8, 4);
+    assert!(run(&mut r, &input) == run(&mut other, &input));
+}
@@ -0,0 +1,158 @@
+use cloudseedcore_rs::{MeterPoint, ParamId, ReverbBuses, ReverbController, DARK_PLATE};
+use std::f64::consts::PI;
+
+mod common;
+use common::noise;
+
+const BLOCK: usize = 256;
+
+fn sine(len: usize, freq: f64, amplitude: f64, phase: f64) -> Vec<f32> {
+    (0..len)
+        .map(|i| (amplitude * (2.0 * PI * freq * i as f64 / 48000.0 + phase).sin()) as f32)
+        .collect()
+}
+
+fn metered() -> ReverbController {
+    let mut r = ReverbController::with_seed(48000.0, BLOCK as u32, 1);
+    r.set_program(&DARK_PLATE);
+    r.set_metering(true);
+    r
+}
+
+/// Processes the signal in blocks, discarding the output.
+fn run(r: &mut ReverbController, in_l: &[f32], in_r: &[f32]) {
+    let mut out_l = vec![0.0f32; in_l.len()];
+    let mut out_r = vec![0.0f32; in_l.len()];
+    r.process_any_len(in_l, in_r, &mut out_l, &mut out_r);
+}
+
+#[test]
+fn metering_is_disabled_by_default() {
+    let mut r = ReverbController::with_seed(48000.0, BLOCK as u32, 1);
+    r.set_program(&DARK_PLATE);
+    assert!(!r.is_metering());
+
+    let input = noise(BLOCK * 4, 1);
+    run(&mut r, &input, &input);
+    let reading = r.meters().read(MeterPoint::Input);
+    assert_eq!(reading.peak, [f32::NEG_INFINITY; 2]);
+    assert_eq!(reading.rms, [f32::NEG_INFINITY; 2]);
+    assert_eq!(reading.short_term_lufs, f32::NEG_INFINITY);
+}
+
+#[test]
+fn measures_sine_levels() {
+    let mut r = metered();
+    let meters = r.meters();
+    let left = sine(48000 * 4, 997.0, 0.5, 0.0);
+    let right: Vec<f32> = left.iter().map(|x| -x).collect();
+    run(&mut r, &left, &right);
+
+    let input = meters.read(MeterPoint::Input);
+    for ch in 0..2 {
+        assert!((input.peak[ch] + 6.02).abs() < 0.01, "{input:?}");
+        assert!((input.rms[ch] + 9.03).abs() < 0.05, "{input:?}");
+        assert!(input.true_peak[ch] >= input.peak[ch] - 0.01, "{input:?}");
+    }
+    // a full-scale sine at 997 Hz in one channel reads -3.01 LUFS
+    assert!((input.short_term_lufs + 6.02).abs() < 0.05, "{input:?}");
+    assert!(input.correlation < -0.99, "{input:?}");
+}
+
+#[test]
+fn true_peak_finds_peaks_between_samples() {
+    let mut r = metered();
+    let meters = r.meters();
+    // a quarter of the sample rate, sampled 45 degrees off its peaks
+    let input = sine(48000, 12000.0, 0.5, PI / 4.0);
+    run(&mut r, &input, &input);
+
+    let reading = meters.read(MeterPoint::Input);
+    assert!((reading.peak[0] + 9.03).abs() < 0.01, "{reading:?}");
+    assert!((reading.true_peak[0] + 6.02).abs() < 0.3, "{reading:?}");
+    assert!(reading.correlation > 0.99, "{reading:?}");
+}
+
+#[test]
+fn peaks_are_held_until_read() {
+    let mut r = metered();
+    let meters = r.meters();
+    let mut input = vec![0.0f32; BLOCK * 8];
+    input[10] = 0.25;
+    run(&mut r, &input, &input);
+
+    let reading = meters.read(MeterPoint::Input);
+    assert!((reading.peak[0] + 12.04).abs() < 0.01);
+    assert!(reading.true_peak[0] >= reading.peak[0]);
+
+    run(&mut r, &[0.0; BLOCK], &[0.0; BLOCK]);
+    let reading = meters.read(MeterPoint::Input);
+    assert_eq!(reading.peak, [f32::NEG_INFINITY; 2]);
+    assert_eq!(reading.true_peak, [f32::NEG_INFINITY; 2]);
+}
+
+#[test]
+fn meters_buses_and_output() {
+    let mut r = metered();
+    r.set_parameter(ParamId::DryOut, 0.0);
+    let meters = r.meters();
+    let input = noise(BLOCK, 2);
+
+    let mut out_l = [0.0f32; BLOCK];
+    let mut out_r = [0.0f32; BLOCK];
+    let mut bus_bufs = [[0.0f32; BLOCK]; 6];
+    let mut peaks = [0.0f32; 3];
+    for _ in 0..32 {
+        let [dry_l, dry_r, early_l, early_r, late_l, late_r] = &mut bus_bufs;
+        let mut buses = ReverbBuses {
+            dry_l,
+            dry_r,
+            early_l,
+            early_r,
+            late_l,
+            late_r,
+        };
+        r.process_buses(&input, &input, &mut out_l, &mut out_r, &mut buses, BLOCK as u32);
+        let peak = |x: &[f32]| x.iter().fold(0.0f32, |m, x| m.max(x.abs()));
+        peaks[0] = peaks[0].max(peak(buses.early_l));
+        peaks[1] = peaks[1].max(peak(buses.late_l));
+        peaks[2] = peaks[2].max(peak(&out_l));
+    }
+
+    let points = [MeterPoint::Early, MeterPoint::Late, MeterPoint::Output];
+    for (point, peak) in points.into_iter().zip(peaks) {
+        let reading = meters.read(point);
+        assert!(peak > 0.0);
+        assert!((reading.peak[0] - 20.0 * peak.log10()).abs() < 1e-3, "{point:?}");
+    }
+
+    // without buses, the early and late signals are still measured
+    run(&mut r, &input, &input);
+    assert!(meters.read(MeterPoint::Late).peak[0] > -60.0);
+}
+
+#[test]
+fn meters_can_be_read_from_another_thread() {
+    let mut r = metered();
+    let meters = r.meters();
+    let input = noise(BLOCK * 64, 3);
+    let reader = std::thread::spawn(move || {
+        let mut highest = f32::NEG_INFINITY;
+        for _ in 0..1000 {
+            highest = highest.max(meters.read(MeterPoint::Input).peak[0]);
+            std::thread::yield_now();
+        }
+        highest
+    });
+    for _ in 0..16 {
+        run(&mut r, &input, &input);
+    }
+    let highest = reader.join().unwrap();
+    assert!(highest <= 0.0);
+
+    // clones are metered, with their own handle
+    let clone = r.clone();
+    assert!(clone.is_metering());
+    r.set_metering(false);
+    assert!(!r.is_metering());
+}
@@ -0,0 +1,123 @@
+use cloudseedcore_rs::{MonoInput, ParamId, ReverbController, DARK_PLATE};
+
+mod common;
+use common::{dry_only, noise};
+
+const BLOCK: usize = 256;
+
+fn reverb(mode: MonoInput) -> ReverbController {
+    let mut r = ReverbController::with_seed(48000.0, BLOCK as u32, 1);
+    r.set_program(&DARK_PLATE);
+    r.set_mono_input(mode);
+    r
+}
+
+/// Renders the input through `process_mono_to_stereo`.
+fn mono_to_stereo(r: &mut ReverbController, input: &[f32]) -> (Vec<f32>, Vec<f32>) {
+    let (mut left, mut right) = (Vec::new(), Vec::new());
+    let mut out_l = [0.0f32; BLOCK];
+    let mut out_r = [0.0f32; BLOCK];
+    for block in input.chunks(BLOCK) {
+        let n = block.len();
+        r.process_mono_to_stereo(block, &mut out_l, &mut out_r, n as u32);
+        left.extend_from_slice(&out_l[..n]);
+        right.extend_from_slice(&out_r[..n]);
+    }
+    (left, right)
+}
+
+fn correlation(a: &[f32], b: &[f32]) -> f32 {
+    let dot = |x: &[f32], y: &[f32]| x.iter().zip(y).map(|(p, q)| p * q).sum::<f32>();
+    dot(a, b) / (dot(a, a) * dot(b, b)).sqrt()
+}
+
+#[test]
+fn duplicate_matches_stereo_processing() {
+    let input = noise(BLOCK * 8, 1);
+    let (left, right) = mono_to_stereo(&mut reverb(MonoInput::Duplicate), &input);
+
+    let mut r = reverb(MonoInput::Duplicate);
+    let mut out_l = vec![0.0; input.len()];
+    let mut out_r = vec![0.0; input.len()];
+    r.process_any_len(&input, &input, &mut out_l, &mut out_r);
+    assert!(left == out_l && right == out_r);
+}
+
+#[test]
+fn decorrelated_input_widens_output() {
+    let mut input = noise(BLOCK * 2, 2);
+    input.resize(BLOCK * 32, 0.0);
+
+    let mut dup = reverb(MonoInput::Duplicate);
+    let mut dec = reverb(MonoInput::Decorrelated);
+    dup.set_parameter(ParamId::DryOut, 0.0);
+    dec.set_parameter(ParamId::DryOut, 0.0);
+    let (dup_l, dup_r) = mono_to_stereo(&mut dup, &input);
+    let (dec_l, dec_r) = mono_to_stereo(&mut dec, &input);
+
+    assert!(dec_r != dup_r);
+    assert!(correlation(&dec_l, &dec_r).abs() < correlation(&dup_l, &dup_r).abs());
+    assert!(dec_r.iter().all(|x| x.is_finite()));
+}
+
+#[test]
+fn stereo_to_mono_averages_channels() {
+    let (in_l, in_r) = (noise(BLOCK, 3), noise(BLOCK, 4));
+    let mut a = reverb(MonoInput::Duplicate);
+    let mut b = reverb(MonoInput::Duplicate);
+
+    let mut mono = [0.0f32; BLOCK];
+    a.process_stereo_to_mono(&in_l, &in_r, &mut mono, BLOCK as u32);
+
+    let mut out_l = [0.0f32; BLOCK];
+    let mut out_r = [0.0f32; BLOCK];
+    b.process(&in_l, &in_r, &mut out_l, &mut out_r, BLOCK as u32);
+    for i in 0..BLOCK {
+        assert_eq!(mono[i], 0.5 * (out_l[i] + out_r[i]));
+    }
+}
+
+#[test]
+fn mono_to_mono_matches_mono_to_stereo_sum() {
+    let input = noise(BLOCK * 4, 5);
+    let (left, right) = mono_to_stereo(&mut reverb(MonoInput::Decorrelated), &input);
+
+    let mut r = reverb(MonoInput::Decorrelated);
+    let mut out = [0.0f32; BLOCK];
+    for (i, block) in input.chunks(BLOCK).enumerate() {
+        r.process_mono(block, &mut out, BLOCK as u32);
+        for j in 0..BLOCK {
+            assert_eq!(out[j], 0.5 * (left[i * BLOCK + j] + right[i * BLOCK + j]));
+        }
+    }
+}
+
+#[test]
+fn dry_signal_is_not_decorrelated() {
+    let input = noise(BLOCK, 8);
+    for mode in [MonoInput::Duplicate, MonoInput::Decorrelated] {
+        for input_mix in [0.0, 1.0] {
+            let mut r = reverb(mode);
+            r.set_program(&dry_only());
+            r.set_parameter(ParamId::InputMix, input_mix);
+
+            let mut out = [0.0f32; BLOCK];
+            r.process_mono(&input, &mut out, BLOCK as u32);
+            assert!(out == *input, "{mode:?} {input_mix}");
+
+            let (left, right) = mono_to_stereo(&mut r, &input);
+            assert!(left == input && right == input, "{mode:?} {input_mix}");
+        }
+    }
+}
+
+#[test]
+fn fork_continues_decorrelated_input() {
+    let mut r = reverb(MonoInput::Decorrelated);
+    mono_to_stereo(&mut r, &noise(BLOCK * 4, 6));
+
+    let mut forked = r.fork();
+    assert_eq!(forked.mono_input(), MonoInput::Decorrelated);
+    let input = noise(BLOCK * 4, 7);
+    assert!(mono_to_stereo(&mut r, &input) == mono_to_stereo(&mut forked, &input));
+}
@@ -0,0 +1,116 @@
+use cloudseedcore_rs::{
+    EngineSample, Error, Interleaved, ParamId, ReverbController, ReverbControllerF64, DARK_PLATE,
+};
+
+mod common;
+use common::{noise, run};
+
+const BLOCK: usize = 256;
+
+fn rms(signal: impl Iterator<Item = f64>) -> f64 {
+    let (sum, n) = signal.fold((0.0, 0), |(sum, n), x| (sum + x * x, n + 1));
+    (sum / n as f64).sqrt()
+}
+
+/// A plate with a very long decay.
+fn long_decay<S: EngineSample>(r: &mut ReverbController<S>) {
+    r.set_program(&DARK_PLATE);
+    r.set_parameter(ParamId::LateLineDecay, 1.0);
+    r.set_parameter(ParamId::DryOut, 0.0);
+}
+
+#[test]
+fn f64_engine_matches_f32_engine() {
+    let mut single = ReverbController::with_seed(48000.0, BLOCK as u32, 1);
+    let mut double = ReverbControllerF64::with_seed_f64(48000.0, BLOCK as u32, 1);
+    single.set_program(&DARK_PLATE);
+    double.set_program(&DARK_PLATE);
+    assert_eq!(double.get_program(), single.get_program());
+
+    let input = noise(BLOCK * 64, 1);
+    let input_f64: Vec<f64> = input.iter().map(|&x| x as f64).collect();
+    let a = run(&mut single, &input);
+    let b = run(&mut double, &input_f64);
+
+    let signal = rms(a.iter().map(|&x| x as f64));
+    let error = rms(a.iter().zip(&b).map(|(&x, &y)| x as f64 - y));
+    assert!(signal > 0.01, "{signal}");
+    assert!(error < signal * 1e-3, "{error} {signal}");
+}
+
+#[test]
+fn f64_engine_has_lower_rounding_noise() {
+    // the reverb is linear, so the output for the sum of two signals
+    // only differs from the sum of the outputs by rounding errors.
+    // the inputs stay clear of zero and the input filters are disabled,
+    // as they flush tiny values to zero, which isn't linear
+    fn superposition_error<S: EngineSample>(mut create: impl FnMut() -> ReverbController<S>) -> f64 {
+        let len = 48000 * 2;
+        let burst = |seed| -> Vec<S> {
+            let burst = noise(len / 4, seed);
+            burst.iter().map(|&x| S::from_f32(0.1 + 0.4 * x.abs())).collect()
+        };
+        let mut a = burst(2);
+        let mut b = burst(3);
+        a.resize(len, S::default());
+        b.resize(len, S::default());
+        let sum: Vec<S> = a.iter().zip(&b).map(|(&x, &y)| x + y).collect();
+
+        let mut outputs = [a, b, sum].map(|input| {
+            let mut r = create();
+            long_decay(&mut r);
+            r.set_parameter(ParamId::LowCutEnabled, 0.0);
+            r.set_parameter(ParamId::HighCutEnabled, 0.0);
+            run(&mut r, &input)
+        });
+        let [out_a, out_b, out_sum] = &mut outputs;
+        rms((0..out_sum.len()).map(|i| (out_sum[i] - out_a[i] - out_b[i]).to_f64()))
+    }
+
+    let single = superposition_error(|| ReverbController::with_seed(48000.0, BLOCK as u32, 4));
+    let double = superposition_error(|| ReverbControllerF64::with_seed_f64(48000.0, BLOCK as u32, 4));
+    assert!(double < single * 1e-3, "{double} {single}");
+}
+
+#[test]
+fn f64_snapshot_and_fork_continue_tail() {
+    let mut r = ReverbControllerF64::with_seed_f64(48000.0, BLOCK as u32, 5);
+    long_decay(&mut r);
+    let input: Vec<f64> = noise(BLOCK * 8, 5).iter().map(|&x| x as f64).collect();
+    run(&mut r, &input);
+
+    let mut forked = r.fork();
+    let mut restored = ReverbControllerF64::with_seed_f64(44100.0, BLOCK as u32, 6);
+    restored.restore(&r.snapshot()).unwrap();
+
+    let expected = run(&mut r, &input);
+    assert!(run(&mut forked, &input) == expected);
+    assert!(run(&mut restored, &input) == expected);
+}
+
+#[test]
+fn snapshots_are_not_restored_across_precisions() {
+    let single = ReverbController::with_seed(48000.0, BLOCK as u32, 7);
+    let mut double = ReverbControllerF64::with_seed_f64(48000.0, BLOCK as u32, 7);
+    assert!(matches!(double.restore(&single.snapshot()), Err(Error::InvalidSnapshot)));
+
+    let mut single = single;
+    assert!(matches!(single.restore(&double.snapshot()), Err(Error::InvalidSnapshot)));
+}
+
+#[test]
+fn f64_engine_converts_sample_formats() {
+    let mut r = ReverbControllerF64::try_with_seed_f64(48000.0, BLOCK as u32, 8).unwrap();
+    r.set_program(&DARK_PLATE);
+    let input: Vec<f64> = noise(BLOCK * 4, 8).iter().map(|&x| x as f64 * 0.5).collect();
+    let interleaved: Vec<f64> = input.iter().flat_map(|&x| [x, x]).collect();
+    let mut expected = vec![0.0f64; interleaved.len()];
+    r.clone().process_interleaved(&interleaved, &mut expected);
+
+    let ints: Vec<i32> = interleaved.iter().map(|&x| (x * 2_147_483_648.0) as i32).collect();
+    let mut output = vec![0.0f32; ints.len()];
+    r.process_buffers(&Interleaved(&ints[..]), &mut Interleaved(&mut output[..]));
+    for (&x, &y) in output.iter().zip(&expected) {
+        assert!((x as f64 - y).abs() < 1e-6, "{x} {y}");
+    }
+}
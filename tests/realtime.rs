@@ -0,0 +1,228 @@
+use cloudseedcore_rs::{
+    Interleaved, MeterPoint, MonoInput, ParamId, Program, ReverbBuses, ReverbController, ReverbControllerF64, ReverbEvent, SmoothingMode, DARK_PLATE,
+};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+thread_local! {
+    /// Whether allocations on this thread are being counted.
+    static TRACKING: Cell<bool> = const { Cell::new(false) };
+    /// Number of allocations made by Rust code while tracking.
+    static RUST_ALLOCS: Cell<usize> = const { Cell::new(0) };
+    /// Number of allocations made by C++ `operator new` while tracking.
+    static CXX_ALLOCS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn count(counter: &'static std::thread::LocalKey<Cell<usize>>) {
+    if TRACKING.with(Cell::get) {
+        counter.with(|c| c.set(c.get() + 1));
+    }
+}
+
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count(&RUST_ALLOCS);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        count(&RUST_ALLOCS);
+        unsafe { System.alloc_zeroed(layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count(&RUST_ALLOCS);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Replacements for the C++ `operator new` and `operator new[]`,
+/// so allocations inside CloudSeedCore are counted too.
+/// The default `operator delete` frees with `free`, which matches these.
+#[cfg(target_os = "linux")]
+mod cxx_new {
+    use std::ffi::c_void;
+
+    unsafe extern "C" {
+        fn malloc(size: usize) -> *mut c_void;
+    }
+
+    fn new(size: usize) -> *mut c_void {
+        super::count(&super::CXX_ALLOCS);
+        let ptr = unsafe { malloc(size.max(1)) };
+        if ptr.is_null() {
+            std::process::abort();
+        }
+        ptr
+    }
+
+    #[unsafe(no_mangle)]
+    extern "C" fn _Znwm(size: usize) -> *mut c_void {
+        new(size)
+    }
+
+    #[unsafe(no_mangle)]
+    extern "C" fn _Znam(size: usize) -> *mut c_void {
+        new(size)
+    }
+}
+
+/// Runs `f` and returns the number of Rust and C++ allocations it made on this thread.
+fn count_allocations(f: impl FnOnce()) -> (usize, usize) {
+    RUST_ALLOCS.with(|c| c.set(0));
+    CXX_ALLOCS.with(|c| c.set(0));
+    TRACKING.with(|t| t.set(true));
+    f();
+    TRACKING.with(|t| t.set(false));
+    (RUST_ALLOCS.with(Cell::get), CXX_ALLOCS.with(Cell::get))
+}
+
+const BLOCK: usize = 256;
+
+#[test]
+#[cfg(target_os = "linux")]
+fn allocation_tracking_sees_cxx_allocations() {
+    let (_, cxx) = count_allocations(|| {
+        let _ = ReverbController::new(48000.0, BLOCK as u32);
+    });
+    assert!(cxx > 0);
+}
+
+#[test]
+fn parameter_and_program_changes_do_not_allocate() {
+    let mut r = ReverbController::new(48000.0, BLOCK as u32);
+    r.set_smoothing(0.01, SmoothingMode::Exponential);
+    let mut other = DARK_PLATE;
+    other.seed_tap = 0.3;
+    other.seed_diffusion = 0.6;
+
+    let allocs = count_allocations(|| {
+        for (i, &id) in ParamId::ALL.iter().enumerate() {
+            // seeds, line counts and sizes all rebuild seed-dependent tables
+            r.set_parameter(id, 0.25 + (i % 3) as f32 * 0.25);
+            r.set_parameter(id, 0.9);
+        }
+        r.set_program(&DARK_PLATE);
+        r.set_program(&other);
+        r.set_program(&Program::from_array([0.5; 45]));
+        r.set_freeze(true);
+        r.set_freeze(false);
+    });
+    assert_eq!(allocs, (0, 0));
+}
+
+#[test]
+fn processing_does_not_allocate() {
+    let mut r = ReverbController::new(48000.0, BLOCK as u32);
+    r.set_program(&DARK_PLATE);
+    r.set_smoothing(0.005, SmoothingMode::Linear);
+
+    // the first crossfade allocates the second engine; later ones must reuse it
+    r.set_program_crossfade(&DARK_PLATE, 0.01);
+
+    let input = [0.5f32; BLOCK];
+    let mut out_l = [0.0f32; BLOCK];
+    let mut out_r = [0.0f32; BLOCK];
+    let mut bus_bufs = [[0.0f32; BLOCK]; 6];
+    let events = [
+        ReverbEvent::Parameter {
+            sample_offset: 10,
+            id: ParamId::SeedDelay,
+            value: 0.4,
+        },
+        ReverbEvent::Program {
+            sample_offset: 100,
+            program: DARK_PLATE,
+        },
+    ];
+
+    let long = vec![0.5f32; BLOCK * 3 + 10];
+    let mut long_l = vec![0.0f32; long.len()];
+    let mut long_r = vec![0.0f32; long.len()];
+    let ints = vec![1000i16; long.len()];
+    let mut doubles = vec![0.0f64; long.len() * 2];
+
+    let params = r.params();
+    // enabling metering allocates its buffers; metered processing must not
+    r.set_metering(true);
+    let meters = r.meters();
+    // the same goes for silence detection
+    r.set_silence_threshold(Some(-90.0));
+
+    let allocs = count_allocations(|| {
+        r.set_parameter(ParamId::LateOut, 0.2);
+        params.set_parameter(ParamId::SeedTap, 0.7);
+        params.set_program(&DARK_PLATE);
+        params.reset();
+        for _ in 0..8 {
+            r.process(&input, &input, &mut out_l, &mut out_r, BLOCK as u32);
+        }
+
+        r.set_program_crossfade(&DARK_PLATE, 0.01);
+        let [dry_l, dry_r, early_l, early_r, late_l, late_r] = &mut bus_bufs;
+        let mut buses = ReverbBuses {
+            dry_l,
+            dry_r,
+            early_l,
+            early_r,
+            late_l,
+            late_r,
+        };
+        for _ in 0..4 {
+            r.process_buses(&input, &input, &mut out_l, &mut out_r, &mut buses, BLOCK as u32);
+        }
+
+        r.process_with_events(&input, &input, &mut out_l, &mut out_r, BLOCK as u32, &events);
+        r.try_process(&input, &input, &mut out_l, &mut out_r, BLOCK as u32)
+            .unwrap();
+
+        r.process_any_len(&long, &long, &mut long_l, &mut long_r);
+        r.process_in_place(&mut long_l, &mut long_r);
+        r.process_interleaved(&long, &mut long_l);
+        r.process_buffers(&[&ints[..], &ints[..]], &mut Interleaved(&mut doubles[..]));
+
+        r.set_mono_input(MonoInput::Decorrelated);
+        r.process_mono_to_stereo(&input, &mut out_l, &mut out_r, BLOCK as u32);
+        r.process_stereo_to_mono(&input, &input, &mut out_l, BLOCK as u32);
+        r.process_mono(&input, &mut out_r, BLOCK as u32);
+
+        r.reset();
+        r.process(&[0.0; BLOCK], &[0.0; BLOCK], &mut out_l, &mut out_r, BLOCK as u32);
+        r.process(&input, &input, &mut out_l, &mut out_r, BLOCK as u32);
+        meters.read(MeterPoint::Output);
+    });
+    assert_eq!(allocs, (0, 0));
+}
+
+#[test]
+fn double_precision_processing_does_not_allocate() {
+    let mut r = ReverbControllerF64::new_f64(48000.0, BLOCK as u32);
+    r.set_program(&DARK_PLATE);
+    r.set_program_crossfade(&DARK_PLATE, 0.01);
+
+    let input = [0.5f64; BLOCK];
+    let mut out_l = [0.0f64; BLOCK];
+    let mut out_r = [0.0f64; BLOCK];
+    let ints = vec![1000i16; BLOCK * 3];
+    let mut floats = vec![0.0f32; BLOCK * 3];
+
+    let allocs = count_allocations(|| {
+        r.set_program_crossfade(&DARK_PLATE, 0.01);
+        for _ in 0..4 {
+            r.process(&input, &input, &mut out_l, &mut out_r, BLOCK as u32);
+        }
+        r.process_buffers(&Interleaved(&ints[..]), &mut Interleaved(&mut floats[..]));
+        r.set_mono_input(MonoInput::Decorrelated);
+        r.process_mono(&input, &mut out_l, BLOCK as u32);
+    });
+    assert_eq!(allocs, (0, 0));
+}
@@ -1,4 +1,7 @@
-use cloudseedcore_rs::{ParamId, ReverbController, DARK_PLATE};
+use cloudseedcore_rs::{DARK_PLATE, MIN_SAMPLE_RATE, ParamId, ReverbController, ReverbEvent};
+
+mod common;
+use common::{dry_only, noise, run};
 
 #[test]
 fn new_reverb_has_correct_block_size() {
@@ -24,6 +27,16 @@ fn sample_rate_updates() {
     // should not panic or crash
 }
 
+#[test]
+fn filters_above_nyquist_stay_stable() {
+    // the high shelf of the Dark Plate is at 4.5 kHz, above the Nyquist frequency
+    let mut r = ReverbController::with_seed(MIN_SAMPLE_RATE, 256, 1);
+    r.set_program(&DARK_PLATE);
+    let output = run(&mut r, &noise(MIN_SAMPLE_RATE as usize, 1));
+    assert!(output.iter().all(|x| x.is_finite()));
+    assert!(output.iter().any(|&x| x != 0.0));
+}
+
 #[test]
 fn reset_does_not_panic() {
     let mut r = ReverbController::new(44100.0, 64);
@@ -52,3 +65,201 @@ fn program_set_then_modify_parameter() {
 
     assert_eq!(v, 0.123);
 }
+
+#[test]
+fn events_are_applied_sample_accurately() {
+    let mut r = ReverbController::new(48000.0, 256);
+    r.set_program(&dry_only());
+
+    let in_l = vec![0.5f32; 256];
+    let in_r = vec![-0.5f32; 256];
+    let mut out_l = vec![0.0f32; 256];
+    let mut out_r = vec![0.0f32; 256];
+
+    let events = [
+        ReverbEvent::Parameter {
+            sample_offset: 100,
+            id: ParamId::DryOut,
+            value: 0.0,
+        },
+        ReverbEvent::Parameter {
+            sample_offset: 200,
+            id: ParamId::DryOut,
+            value: 1.0,
+        },
+    ];
+    r.process_with_events(&in_l, &in_r, &mut out_l, &mut out_r, 256, &events);
+
+    for i in 0..256 {
+        let expected = if (100..200).contains(&i) { 0.0 } else { 0.5 };
+        assert_eq!(out_l[i], expected, "left sample {i}");
+        assert_eq!(out_r[i], -expected, "right sample {i}");
+    }
+    assert_eq!(r.get_parameter(ParamId::DryOut), 1.0);
+}
+
+#[test]
+fn program_event_is_applied() {
+    let mut r = ReverbController::new(48000.0, 64);
+    r.set_program(&dry_only());
+
+    let input = vec![0.0f32; 64];
+    let mut out_l = vec![0.0f32; 64];
+    let mut out_r = vec![0.0f32; 64];
+
+    let events = [ReverbEvent::Program {
+        sample_offset: 32,
+        program: DARK_PLATE,
+    }];
+    r.process_with_events(&input, &input, &mut out_l, &mut out_r, 64, &events);
+
+    assert_eq!(r.get_program(), DARK_PLATE);
+}
+
+#[test]
+#[should_panic]
+fn unsorted_events_panic() {
+    let mut r = ReverbController::new(48000.0, 64);
+
+    let input = vec![0.0f32; 64];
+    let mut out_l = vec![0.0f32; 64];
+    let mut out_r = vec![0.0f32; 64];
+
+    let events = [
+        ReverbEvent::Parameter {
+            sample_offset: 20,
+            id: ParamId::DryOut,
+            value: 0.0,
+        },
+        ReverbEvent::Parameter {
+            sample_offset: 10,
+            id: ParamId::DryOut,
+            value: 1.0,
+        },
+    ];
+    r.process_with_events(&input, &input, &mut out_l, &mut out_r, 64, &events);
+}
+
+#[test]
+fn program_crossfade_ramps_between_programs() {
+    let mut r = ReverbController::new(48000.0, 256);
+    r.set_program(&dry_only());
+
+    let mut muted = dry_only();
+    muted.dry_out = 0.0;
+    r.set_program_crossfade(&muted, 256.0 / 48000.0);
+    assert!(r.is_crossfading());
+    assert_eq!(r.get_program(), muted);
+
+    let input = vec![1.0f32; 256];
+    let mut out_l = vec![0.0f32; 256];
+    let mut out_r = vec![0.0f32; 256];
+    r.process(&input, &input, &mut out_l, &mut out_r, 256);
+
+    assert_eq!(out_l[0], 1.0);
+    assert!((out_l[128] - 0.5).abs() < 1e-6);
+    assert!(out_l.windows(2).all(|w| w[1] < w[0]));
+    assert!(!r.is_crossfading());
+
+    r.process(&input, &input, &mut out_l, &mut out_r, 256);
+    assert!(out_l.iter().all(|&x| x == 0.0));
+}
+
+#[test]
+fn program_crossfade_preserves_tail() {
+    let block = 512;
+    let mut r = ReverbController::new(48000.0, block as u32);
+    r.set_program(&DARK_PLATE);
+    r.set_parameter(ParamId::DryOut, 0.0);
+
+    let impulse = vec![1.0f32; block];
+    let silence = vec![0.0f32; block];
+    let mut out_l = vec![0.0f32; block];
+    let mut out_r = vec![0.0f32; block];
+    for _ in 0..8 {
+        r.process(&impulse, &impulse, &mut out_l, &mut out_r, block as u32);
+    }
+
+    // switch to a program that produces no output on its own
+    let mut muted = dry_only();
+    muted.dry_out = 0.0;
+    r.set_program_crossfade(&muted, 0.1);
+
+    r.process(&silence, &silence, &mut out_l, &mut out_r, block as u32);
+    let energy: f32 = out_l.iter().map(|x| x * x).sum();
+    assert!(energy > 1e-6, "tail was cut off, energy {energy}");
+}
+
+#[test]
+fn freeze_sustains_tail() {
+    let block = 512;
+    let mut r = ReverbController::new(48000.0, block as u32);
+    r.set_program(&DARK_PLATE);
+    r.set_parameter(ParamId::DryOut, 0.0);
+
+    let excitation = noise(block, 1);
+    let silence = vec![0.0f32; block];
+    let mut out_l = vec![0.0f32; block];
+    let mut out_r = vec![0.0f32; block];
+    let mut block_energy = |r: &mut ReverbController, input: &[f32]| {
+        r.process(input, input, &mut out_l, &mut out_r, block as u32);
+        out_l.iter().chain(&out_r).map(|x| x * x).sum::<f32>()
+    };
+
+    for _ in 0..8 {
+        block_energy(&mut r, &excitation);
+    }
+    r.set_freeze(true);
+    assert!(r.is_frozen());
+
+    // let the freeze ramp complete, feeding input that must not reach the tank
+    for _ in 0..10 {
+        block_energy(&mut r, &excitation);
+    }
+    let early = block_energy(&mut r, &silence);
+
+    // ~3 seconds later, the tail should still be there at a similar level
+    for _ in 0..280 {
+        block_energy(&mut r, &silence);
+    }
+    let late = block_energy(&mut r, &silence);
+    assert!(late > early * 0.5, "frozen tail decayed from {early} to {late}");
+    assert!(late < early * 2.0, "frozen tail grew from {early} to {late}");
+
+    // after release, the tail decays again
+    r.set_freeze(false);
+    for _ in 0..280 {
+        block_energy(&mut r, &silence);
+    }
+    let released = block_energy(&mut r, &silence);
+    assert!(
+        released < late * 0.1,
+        "released tail did not decay: {late} -> {released}"
+    );
+}
+
+#[test]
+fn process_any_len_matches_block_processing() {
+    let block = 256;
+    let input = noise(block * 20 + 123, 2);
+    let len = input.len();
+
+    let mut a = ReverbController::with_seed(48000.0, block as u32, 1);
+    let mut b = ReverbController::with_seed(48000.0, block as u32, 1);
+    a.set_program(&DARK_PLATE);
+    b.set_program(&DARK_PLATE);
+
+    let mut whole_l = vec![0.0f32; len];
+    let mut whole_r = vec![0.0f32; len];
+    a.process_any_len(&input, &input, &mut whole_l, &mut whole_r);
+
+    let mut out_l = vec![0.0f32; len];
+    let mut out_r = vec![0.0f32; len];
+    for (i, chunk) in input.chunks(block).enumerate() {
+        let range = i * block..i * block + chunk.len();
+        b.process(chunk, chunk, &mut out_l[range.clone()], &mut out_r[range], chunk.len() as u32);
+    }
+
+    assert!(whole_l.iter().any(|&x| x != 0.0));
+    assert!(whole_l == out_l && whole_r == out_r);
+}
@@ -0,0 +1,97 @@
+use cloudseedcore_rs::{ParamId, ReverbController, ReverbParams, SmoothingMode, DARK_PLATE};
+
+const BLOCK: usize = 256;
+
+fn process_silence(r: &mut ReverbController) -> (Vec<f32>, Vec<f32>) {
+    let input = [0.0f32; BLOCK];
+    let mut out_l = vec![0.0f32; BLOCK];
+    let mut out_r = vec![0.0f32; BLOCK];
+    r.process(&input, &input, &mut out_l, &mut out_r, BLOCK as u32);
+    (out_l, out_r)
+}
+
+#[test]
+fn params_handle_is_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<ReverbParams>();
+}
+
+#[test]
+fn parameter_changes_apply_on_next_process() {
+    let mut r = ReverbController::new(48000.0, BLOCK as u32);
+    let params = r.params();
+
+    std::thread::scope(|s| {
+        s.spawn(|| params.set_parameter(ParamId::LateOut, 0.42));
+    });
+    assert_eq!(params.get_parameter(ParamId::LateOut), 0.42);
+
+    process_silence(&mut r);
+    assert_eq!(r.get_parameter(ParamId::LateOut), 0.42);
+}
+
+#[test]
+fn program_changes_apply_on_next_process() {
+    let mut r = ReverbController::new(48000.0, BLOCK as u32);
+    let params = r.params();
+
+    params.set_program(&DARK_PLATE);
+    assert_eq!(params.get_program(), DARK_PLATE);
+
+    process_silence(&mut r);
+    assert_eq!(r.get_program(), DARK_PLATE);
+}
+
+#[test]
+fn handle_reflects_controller_changes() {
+    let mut r = ReverbController::new(48000.0, BLOCK as u32);
+    let params = r.params();
+
+    r.set_program(&DARK_PLATE);
+    assert_eq!(params.get_program(), DARK_PLATE);
+
+    r.set_parameter(ParamId::DryOut, 0.1);
+    assert_eq!(params.get_parameter(ParamId::DryOut), 0.1);
+
+    // values applied directly on the controller are not re-applied on process
+    process_silence(&mut r);
+    assert_eq!(r.get_parameter(ParamId::DryOut), 0.1);
+}
+
+#[test]
+fn reset_through_handle_ends_tail() {
+    let mut r = ReverbController::new(48000.0, BLOCK as u32);
+    r.set_program(&DARK_PLATE);
+
+    let input = [0.5f32; BLOCK];
+    let mut out_l = [0.0f32; BLOCK];
+    let mut out_r = [0.0f32; BLOCK];
+    for _ in 0..16 {
+        r.process(&input, &input, &mut out_l, &mut out_r, BLOCK as u32);
+    }
+    let (tail_l, _) = process_silence(&mut r);
+    assert!(tail_l.iter().any(|&x| x != 0.0));
+
+    r.params().reset();
+    let (out_l, out_r) = process_silence(&mut r);
+    assert!(out_l.iter().chain(&out_r).all(|&x| x == 0.0));
+}
+
+#[test]
+fn out_of_range_values_are_clamped_like_the_engine() {
+    let mut r = ReverbController::new(48000.0, BLOCK as u32);
+    r.set_program(&DARK_PLATE);
+    r.set_smoothing(0.01, SmoothingMode::Linear);
+    let params = r.params();
+
+    for (value, clamped) in [(2.0, 1.0), (-1.0, 0.0), (f32::NAN, 0.0)] {
+        r.set_parameter(ParamId::LateOut, value);
+        assert_eq!(params.get_parameter(ParamId::LateOut), clamped);
+        assert_eq!(r.get_parameter(ParamId::LateOut), clamped);
+
+        params.set_parameter(ParamId::EarlyOut, value);
+        assert_eq!(params.get_parameter(ParamId::EarlyOut), clamped);
+        process_silence(&mut r);
+        assert_eq!(r.get_parameter(ParamId::EarlyOut), clamped);
+    }
+}
@@ -0,0 +1,157 @@
+use cloudseedcore_rs::{ParamId, ReverbController, DARK_PLATE, DEFAULT_TAIL_THRESHOLD_DB};
+
+mod common;
+use common::noise;
+
+const BLOCK: usize = 256;
+
+/// Processes one block and returns the interleaved output.
+fn block(r: &mut ReverbController, input: &[f32]) -> Vec<f32> {
+    let mut out_l = [0.0f32; BLOCK];
+    let mut out_r = [0.0f32; BLOCK];
+    r.process(input, input, &mut out_l, &mut out_r, BLOCK as u32);
+    [out_l, out_r].concat()
+}
+
+/// A reverb with a short tail, excited by a noise burst.
+fn excited(seed: u64) -> ReverbController {
+    let mut r = ReverbController::with_seed(48000.0, BLOCK as u32, seed);
+    r.set_program(&DARK_PLATE);
+    r.set_parameter(ParamId::LateLineDecay, 0.1);
+    for chunk in noise(BLOCK * 8, 1).chunks(BLOCK) {
+        block(&mut r, chunk);
+    }
+    r
+}
+
+/// Processes silence until the reverb falls asleep, returning the number of blocks.
+fn blocks_until_silent(r: &mut ReverbController, max_blocks: usize) -> Option<usize> {
+    let silence = [0.0f32; BLOCK];
+    (1..=max_blocks).find(|_| {
+        block(r, &silence);
+        r.is_silent()
+    })
+}
+
+#[test]
+fn detection_is_disabled_by_default() {
+    let mut r = excited(1);
+    assert_eq!(r.silence_threshold(), None);
+    assert_eq!(blocks_until_silent(&mut r, 48000 * 5 / BLOCK), None);
+}
+
+#[test]
+fn sleeps_once_tail_has_decayed_and_wakes_on_input() {
+    let mut reference = excited(2);
+    let mut r = excited(2);
+    r.set_silence_threshold(Some(-80.0));
+    assert!(!r.is_silent());
+
+    let silence = [0.0f32; BLOCK];
+    let mut blocks = 0;
+    while !r.is_silent() {
+        let expected = block(&mut reference, &silence);
+        assert!(block(&mut r, &silence) == expected);
+        blocks += 1;
+        assert!(blocks < 48000 * 20 / BLOCK, "never fell asleep");
+    }
+
+    // sleeping outputs zeros where the tail is below the threshold
+    for _ in 0..16 {
+        let expected = block(&mut reference, &silence);
+        let output = block(&mut r, &silence);
+        assert!(output.iter().all(|&x| x == 0.0));
+        assert!(expected.iter().all(|x| x.abs() < 1e-4), "{expected:?}");
+    }
+
+    let input = noise(BLOCK, 3);
+    let output = block(&mut r, &input);
+    assert!(!r.is_silent());
+    assert!(output.iter().any(|&x| x != 0.0));
+}
+
+#[test]
+fn waits_for_predelay() {
+    let mut r = ReverbController::with_seed(48000.0, BLOCK as u32, 4);
+    r.set_program(&DARK_PLATE);
+    r.set_parameter(ParamId::TapPredelay, 1.0);
+    r.set_parameter(ParamId::DryOut, 0.0);
+    r.set_silence_threshold(Some(-80.0));
+
+    let mut impulse = [0.0f32; BLOCK];
+    impulse[0] = 1.0;
+    block(&mut r, &impulse);
+
+    // nothing comes out during the predelay, yet the reverb must stay awake
+    let silence = [0.0f32; BLOCK];
+    let mut heard = false;
+    for _ in 0..48000 * 2 / BLOCK {
+        heard |= block(&mut r, &silence).iter().any(|x| x.abs() > 1e-3);
+        assert!(!r.is_silent() || heard);
+    }
+    assert!(heard);
+}
+
+#[test]
+fn does_not_sleep_while_frozen() {
+    let mut r = excited(5);
+    r.set_freeze(true);
+    r.set_silence_threshold(Some(0.0));
+    assert_eq!(blocks_until_silent(&mut r, 48000 * 2 / BLOCK), None);
+
+    r.set_freeze(false);
+    assert!(blocks_until_silent(&mut r, 48000 * 20 / BLOCK).is_some());
+}
+
+#[test]
+fn reset_silences_immediately() {
+    let mut r = excited(6);
+    r.set_silence_threshold(Some(-90.0));
+    r.reset();
+    assert!(r.is_silent());
+
+    r.set_silence_threshold(None);
+    assert!(!r.is_silent());
+}
+
+#[test]
+fn muted_tail_keeps_reverb_awake() {
+    let mut reference = excited(7);
+    let mut r = excited(7);
+    r.set_silence_threshold(Some(-80.0));
+    for r in [&mut reference, &mut r] {
+        r.set_parameter(ParamId::LateLineDecay, 0.7);
+        r.set_parameter(ParamId::EarlyOut, 0.0);
+        r.set_parameter(ParamId::LateOut, 0.0);
+    }
+
+    // the output falls silent right away, while the tail keeps ringing
+    let silence = [0.0f32; BLOCK];
+    for _ in 0..48000 * 2 / BLOCK {
+        block(&mut reference, &silence);
+        assert!(block(&mut r, &silence).iter().all(|&x| x == 0.0));
+    }
+    assert!(!r.is_silent());
+
+    for r in [&mut reference, &mut r] {
+        r.set_parameter(ParamId::LateOut, DARK_PLATE.late_out);
+    }
+    let expected = block(&mut reference, &silence);
+    assert!(expected.iter().any(|x| x.abs() > 1e-3), "{expected:?}");
+    assert!(block(&mut r, &silence) == expected);
+}
+
+#[test]
+fn crossfade_while_sleeping_loads_program_directly() {
+    let mut r = excited(8);
+    r.set_silence_threshold(Some(-80.0));
+    assert!(blocks_until_silent(&mut r, 48000 * 20 / BLOCK).is_some());
+
+    r.set_program_crossfade(&DARK_PLATE, 1.0);
+    assert!(!r.is_crossfading());
+    assert_eq!(r.get_program(), DARK_PLATE);
+    assert_eq!(
+        r.tail_samples(),
+        DARK_PLATE.estimated_tail(48000.0, DEFAULT_TAIL_THRESHOLD_DB)
+    );
+}
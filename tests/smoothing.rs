@@ -0,0 +1,74 @@
+use cloudseedcore_rs::{ParamId, ReverbController, SmoothingMode};
+
+mod common;
+use common::dry_only;
+
+/// Processes a block of constant input and returns the left output.
+fn process_constant(r: &mut ReverbController, frames: usize) -> Vec<f32> {
+    let input = vec![1.0f32; frames];
+    let mut out_l = vec![0.0f32; frames];
+    let mut out_r = vec![0.0f32; frames];
+    r.process(&input, &input, &mut out_l, &mut out_r, frames as u32);
+    out_l
+}
+
+#[test]
+fn smoothing_is_disabled_by_default() {
+    let mut r = ReverbController::new(48000.0, 512);
+    r.set_program(&dry_only());
+    assert_eq!(r.smoothing_time(), 0.0);
+
+    r.set_parameter(ParamId::DryOut, 0.0);
+    let out = process_constant(&mut r, 512);
+    assert!(out.iter().all(|&x| x == 0.0));
+}
+
+#[test]
+fn continuous_parameter_is_ramped() {
+    for mode in [SmoothingMode::Linear, SmoothingMode::Exponential] {
+        let mut r = ReverbController::new(48000.0, 512);
+        r.set_program(&dry_only());
+        // 10ms = 480 samples
+        r.set_smoothing(0.01, mode);
+
+        r.set_parameter(ParamId::DryOut, 0.0);
+        // the target value is reported immediately
+        assert_eq!(r.get_parameter(ParamId::DryOut), 0.0);
+        assert_eq!(r.get_program().dry_out, 0.0);
+
+        let out = process_constant(&mut r, 512);
+
+        // output fades out gradually instead of jumping
+        assert!(out[0] > 0.5, "{mode:?}: {}", out[0]);
+        assert!(out.windows(2).all(|w| w[1] <= w[0]), "{mode:?}");
+        assert!(out[256] < out[0] && out[256] > 0.0, "{mode:?}");
+
+        // and eventually settles at the target
+        for _ in 0..3 {
+            process_constant(&mut r, 512);
+        }
+        let out = process_constant(&mut r, 512);
+        assert!(out.iter().all(|&x| x == 0.0), "{mode:?}");
+        assert_eq!(r.get_parameter(ParamId::DryOut), 0.0);
+    }
+}
+
+#[test]
+fn structural_parameter_is_stepped() {
+    let mut r = ReverbController::new(48000.0, 64);
+    r.set_smoothing(0.1, SmoothingMode::Linear);
+
+    assert!(!ParamId::LateLineCount.is_continuous());
+    r.set_parameter(ParamId::LateLineCount, 0.5);
+    assert_eq!(r.get_parameter(ParamId::LateLineCount), 0.5);
+}
+
+#[test]
+fn program_load_is_not_smoothed() {
+    let mut r = ReverbController::new(48000.0, 512);
+    r.set_smoothing(0.01, SmoothingMode::Linear);
+    r.set_program(&dry_only());
+
+    let out = process_constant(&mut r, 512);
+    assert!(out.iter().all(|&x| x == 1.0));
+}
@@ -0,0 +1,130 @@
+use cloudseedcore_rs::{
+    Error, ParamId, ReverbController, ReverbSnapshot, SmoothingMode, DARK_PLATE, MAX_SAMPLE_RATE,
+    MIN_SAMPLE_RATE,
+};
+
+mod common;
+use common::{noise, run};
+
+const BLOCK: usize = 256;
+
+/// A reverb with a running tail.
+fn excited() -> ReverbController {
+    let mut r = ReverbController::with_seed(48000.0, BLOCK as u32, 1);
+    r.set_program(&DARK_PLATE);
+    run(&mut r, &noise(BLOCK * 8, 1));
+    r
+}
+
+#[test]
+fn fork_continues_tail() {
+    let mut r = excited();
+    let mut forked = r.fork();
+
+    let input = noise(BLOCK * 16, 2);
+    let a = run(&mut r, &input);
+    let b = run(&mut forked, &input);
+    assert!(a.iter().any(|&x| x != 0.0));
+    assert!(a == b);
+}
+
+#[test]
+fn fork_works_at_every_supported_sample_rate() {
+    for rate in [MIN_SAMPLE_RATE, 44100.0, MAX_SAMPLE_RATE] {
+        let mut r = ReverbController::with_seed(rate, BLOCK as u32, 1);
+        r.set_program(&DARK_PLATE);
+        run(&mut r, &noise(BLOCK * 4, 1));
+        let mut forked = r.fork();
+        assert_eq!(forked.get_sample_rate(), rate);
+
+        let input = noise(BLOCK * 4, 2);
+        assert!(run(&mut r, &input) == run(&mut forked, &input));
+    }
+}
+
+#[test]
+fn restore_into_differently_seeded_instance() {
+    let mut r = excited();
+    let snapshot = r.snapshot();
+
+    let mut other = ReverbController::with_seed(48000.0, BLOCK as u32, 99);
+    other.restore(&snapshot).unwrap();
+    assert_eq!(other.get_program(), DARK_PLATE);
+
+    let input = noise(BLOCK * 16, 3);
+    assert!(run(&mut r, &input) == run(&mut other, &input));
+}
+
+#[test]
+fn restore_undoes_processing() {
+    let mut r = excited();
+    let snapshot = r.snapshot();
+
+    let input = noise(BLOCK * 4, 4);
+    let first = run(&mut r, &input);
+    r.set_parameter(ParamId::LateLineSize, 0.1);
+    run(&mut r, &input);
+
+    r.restore(&snapshot).unwrap();
+    assert_eq!(r.get_program(), DARK_PLATE);
+    assert!(run(&mut r, &input) == first);
+}
+
+#[test]
+fn fork_preserves_freeze_ramps_and_crossfade() {
+    let mut r = excited();
+    r.set_smoothing(0.05, SmoothingMode::Exponential);
+    r.set_parameter(ParamId::LateOut, 0.2);
+    r.set_freeze(true);
+    let mut other = DARK_PLATE;
+    other.late_line_size = 0.3;
+    r.set_program_crossfade(&other, 0.1);
+    run(&mut r, &noise(BLOCK, 5));
+
+    let mut forked = r.fork();
+    assert!(forked.is_crossfading());
+    assert!(forked.is_frozen());
+
+    let input = noise(BLOCK * 24, 6);
+    assert!(run(&mut r, &input) == run(&mut forked, &input));
+}
+
+#[test]
+fn snapshot_bytes_roundtrip() {
+    let mut r = excited();
+    let bytes = r.snapshot().into_bytes();
+
+    let snapshot = ReverbSnapshot::from_bytes(bytes).unwrap();
+    let mut restored = ReverbController::new(44100.0, BLOCK as u32);
+    restored.restore(&snapshot).unwrap();
+
+    let input = noise(BLOCK * 4, 7);
+    assert!(run(&mut r, &input) == run(&mut restored, &input));
+}
+
+#[test]
+fn corrupted_snapshots_are_rejected() {
+    let r = excited();
+    let bytes = r.snapshot().into_bytes();
+
+    let mut wrong_magic = bytes.clone();
+    wrong_magic[0] = b'X';
+    assert_eq!(ReverbSnapshot::from_bytes(wrong_magic).unwrap_err(), Error::InvalidSnapshot);
+
+    let mut target = ReverbController::new(48000.0, BLOCK as u32);
+    target.set_program(&DARK_PLATE);
+    let truncated = ReverbSnapshot::from_bytes(bytes[..bytes.len() - 1].to_vec()).unwrap();
+    assert_eq!(target.restore(&truncated), Err(Error::InvalidSnapshot));
+
+    // out-of-range indices and NaN phases in the DSP state
+    let mut garbage = bytes.clone();
+    let len = garbage.len();
+    garbage[len - 1_000_000..].fill(0xFF);
+    let garbage = ReverbSnapshot::from_bytes(garbage).unwrap();
+    assert_eq!(target.restore(&garbage), Err(Error::InvalidSnapshot));
+
+    // the reverb keeps its program and keeps working
+    assert_eq!(target.get_program(), DARK_PLATE);
+    let output = run(&mut target, &noise(BLOCK * 4, 8));
+    assert!(output.iter().all(|x| x.is_finite()));
+}
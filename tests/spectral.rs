@@ -0,0 +1,99 @@
+use cloudseedcore_rs::{Program, SpectralAnalysis, StereoBuffer, DARK_PLATE};
+
+mod common;
+use common::noise;
+
+const FFT_SIZE: usize = 1024;
+
+/// A plate with a short decay and without damping in the delay lines.
+fn undamped_plate() -> Program {
+    Program {
+        late_line_decay: 0.3,
+        eq_low_shelf_enabled: false,
+        eq_high_shelf_enabled: false,
+        eq_lowpass_enabled: false,
+        ..DARK_PLATE
+    }
+}
+
+fn median(mut values: Vec<f32>) -> f32 {
+    values.sort_by(f32::total_cmp);
+    values[values.len() / 2]
+}
+
+#[test]
+fn unit_impulse_has_flat_response() {
+    let mut impulse = StereoBuffer::new(48000.0, 4800);
+    impulse.left[100] = 1.0;
+    let analysis = SpectralAnalysis::from_impulse_response(&impulse, FFT_SIZE);
+
+    assert_eq!(analysis.frequencies.len(), FFT_SIZE / 2 + 1);
+    assert_eq!(analysis.frequencies[FFT_SIZE / 2], 24000.0);
+    for &db in &analysis.magnitude_db {
+        assert!(db.abs() < 0.01, "{db}");
+    }
+    assert!(analysis.rt60.iter().all(Option::is_none));
+}
+
+#[test]
+fn energy_decay_relief_decreases_over_time() {
+    let mut ir = StereoBuffer::new(48000.0, 48000);
+    ir.left = noise(48000, 1).iter().enumerate().map(|(i, x)| x * (-(i as f32) / 4800.0).exp()).collect();
+    let analysis = SpectralAnalysis::from_impulse_response(&ir, FFT_SIZE);
+
+    let edr = &analysis.energy_decay_relief;
+    assert_eq!(edr.len(), analysis.times.len());
+    assert_eq!(edr[0], analysis.magnitude_db);
+    assert!(analysis.times[0] < 0.0);
+    assert!(analysis.times.windows(2).all(|t| t[1] > t[0]));
+    for frames in edr.windows(2) {
+        assert_eq!(frames[1].len(), analysis.frequencies.len());
+        assert!(frames[0].iter().zip(&frames[1]).all(|(a, b)| b <= a));
+    }
+
+    // the amplitude decays by 1/e every 0.1 s, which is 60 dB in 0.69 s
+    let rt60 = median(analysis.rt60.iter().map(|t| t.unwrap()).collect());
+    assert!((rt60 - 0.69).abs() < 0.05, "{rt60}");
+}
+
+#[test]
+fn lowpass_damping_shortens_high_frequency_decay() {
+    let plain = SpectralAnalysis::from_program(&undamped_plate(), 48000.0, FFT_SIZE);
+    let damped = SpectralAnalysis::from_program(
+        &Program {
+            eq_lowpass_enabled: true,
+            eq_cutoff: 0.5,
+            ..undamped_plate()
+        },
+        48000.0,
+        FFT_SIZE,
+    );
+    let rt60 = |a: &SpectralAnalysis, freq| a.rt60[a.band(freq)].unwrap();
+    assert!(rt60(&damped, 10000.0) < rt60(&plain, 10000.0) * 0.85);
+    assert!((rt60(&damped, 200.0) / rt60(&plain, 200.0) - 1.0).abs() < 0.2);
+
+    let level = |a: &SpectralAnalysis, freq| a.magnitude_db[a.band(freq)];
+    assert!(level(&damped, 10000.0) < level(&plain, 10000.0) - 6.0);
+}
+
+#[test]
+fn high_shelf_damping_colors_the_tail() {
+    let plain = SpectralAnalysis::from_program(&undamped_plate(), 48000.0, FFT_SIZE);
+    let shelved = SpectralAnalysis::from_program(
+        &Program {
+            eq_high_shelf_enabled: true,
+            ..undamped_plate()
+        },
+        48000.0,
+        FFT_SIZE,
+    );
+    let rt60 = |a: &SpectralAnalysis, freq| a.rt60[a.band(freq)].unwrap();
+    assert!(rt60(&shelved, 12000.0) < rt60(&plain, 12000.0));
+    assert!(rt60(&shelved, 12000.0) < rt60(&shelved, 500.0));
+}
+
+#[test]
+#[should_panic]
+fn rejects_fft_size_not_power_of_two() {
+    SpectralAnalysis::from_impulse_response(&StereoBuffer::new(48000.0, 100), 1000);
+}
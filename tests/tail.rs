@@ -0,0 +1,76 @@
+use cloudseedcore_rs::{ParamId, ReverbController, DARK_PLATE, DEFAULT_TAIL_THRESHOLD_DB};
+
+const BLOCK: usize = 256;
+
+/// Returns the impulse response of the reverb, `len` samples long, summed to mono.
+fn impulse_response(r: &mut ReverbController, len: usize) -> Vec<f32> {
+    let mut input = vec![0.0f32; len];
+    input[0] = 1.0;
+    let mut out_l = vec![0.0f32; len];
+    let mut out_r = vec![0.0f32; len];
+    r.process_any_len(&input, &input, &mut out_l, &mut out_r);
+    out_l.iter().zip(&out_r).map(|(l, r)| l.abs().max(r.abs())).collect()
+}
+
+fn reverb(decay: f32) -> ReverbController {
+    let mut r = ReverbController::with_seed(48000.0, BLOCK as u32, 1);
+    r.set_program(&DARK_PLATE);
+    r.set_parameter(ParamId::LateLineDecay, decay);
+    r.set_parameter(ParamId::DryOut, 0.0);
+    r
+}
+
+#[test]
+fn estimate_bounds_measured_tail() {
+    let threshold_db = -60.0;
+    let mut r = reverb(0.3);
+    let estimate = r.get_program().estimated_tail(48000.0, threshold_db) as usize;
+
+    let threshold = 10f32.powf(threshold_db / 20.0);
+    let response = impulse_response(&mut r, estimate * 2);
+    let measured = response.iter().rposition(|&x| x > threshold).unwrap();
+    assert!(measured > 0);
+    assert!(estimate >= measured, "{estimate} {measured}");
+    assert!(estimate < measured * 3, "{estimate} {measured}");
+}
+
+#[test]
+fn estimate_grows_with_decay_and_predelay() {
+    let mut program = DARK_PLATE;
+    let base = program.estimated_tail(48000.0, DEFAULT_TAIL_THRESHOLD_DB);
+
+    program.late_line_decay = (DARK_PLATE.late_line_decay + 0.2).min(1.0);
+    assert!(program.estimated_tail(48000.0, DEFAULT_TAIL_THRESHOLD_DB) > base);
+
+    let mut program = DARK_PLATE;
+    program.tap_predelay = 1.0;
+    let longer = program.estimated_tail(48000.0, DEFAULT_TAIL_THRESHOLD_DB);
+    assert!(longer > base);
+    assert!(longer - base <= 24000);
+
+    assert!(DARK_PLATE.estimated_tail(48000.0, -30.0) < base);
+}
+
+#[test]
+fn muted_outputs_have_no_tail() {
+    let mut program = DARK_PLATE;
+    program.late_out = 0.0;
+    let early_only = program.estimated_tail(48000.0, DEFAULT_TAIL_THRESHOLD_DB);
+    assert!(early_only < DARK_PLATE.estimated_tail(48000.0, DEFAULT_TAIL_THRESHOLD_DB));
+
+    program.early_out = 0.0;
+    assert_eq!(program.estimated_tail(48000.0, DEFAULT_TAIL_THRESHOLD_DB), 0);
+}
+
+#[test]
+fn tail_samples_follows_loaded_program() {
+    let mut r = reverb(0.3);
+    let expected = r.get_program().estimated_tail(48000.0, DEFAULT_TAIL_THRESHOLD_DB);
+    assert_eq!(r.tail_samples(), expected);
+
+    r.set_sample_rate(96000.0);
+    assert!(r.tail_samples() > expected);
+
+    r.set_freeze(true);
+    assert_eq!(r.tail_samples(), u32::MAX);
+}